@@ -0,0 +1,26 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+/// Stand-in for the energy factory. Only exposes the energy view queried by the guilds.
+#[multiversx_sc::contract]
+pub trait EnergyFactoryMock {
+    #[init]
+    fn init(&self) {}
+
+    #[upgrade]
+    fn upgrade(&self) {}
+
+    #[endpoint(setUserEnergy)]
+    fn set_user_energy(&self, user: ManagedAddress, energy: BigUint) {
+        self.user_energy(&user).set(energy);
+    }
+
+    #[view(getEnergyAmountForUser)]
+    fn get_energy_amount_for_user(&self, user: ManagedAddress) -> BigUint {
+        self.user_energy(&user).get()
+    }
+
+    #[storage_mapper("userEnergy")]
+    fn user_energy(&self, user: &ManagedAddress) -> SingleValueMapper<BigUint>;
+}
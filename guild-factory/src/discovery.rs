@@ -0,0 +1,125 @@
+use guild_sc::guild_summary::{GuildSummary, ProxyTrait as _};
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+pub const MAX_PAGE_SIZE: usize = 50;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem)]
+pub struct GuildSummaryResultType<M: ManagedTypeApi> {
+    pub guild: ManagedAddress<M>,
+    pub guild_master: ManagedAddress<M>,
+    pub summary: GuildSummary<M>,
+}
+
+/// `next_cursor` is None once the last guild was visited
+#[derive(TypeAbi, TopEncode, TopDecode)]
+pub struct GuildsPage<M: ManagedTypeApi> {
+    pub guilds: ManagedVec<M, GuildSummaryResultType<M>>,
+    pub next_cursor: Option<usize>,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode)]
+pub struct ClosedGuildsPage<M: ManagedTypeApi> {
+    pub guilds: ManagedVec<M, ManagedAddress<M>>,
+    pub next_cursor: Option<usize>,
+}
+
+/// Paginated views for explorers, so the number of guilds never hits the gas limit.
+/// Cursors are positions in the guild sets, which may shift when guilds are removed.
+#[multiversx_sc::module]
+pub trait DiscoveryModule:
+    crate::factory::FactoryModule
+    + crate::config::ConfigModule
+    + crate::guild_interactions::GuildInteractionsModule
+    + crate::reward_budget::RewardBudgetModule
+    + multiversx_sc_modules::only_admin::OnlyAdminModule
+    + utils::UtilsModule
+{
+    /// Visits at most `page_size` guilds starting at the cursor.
+    /// Only the visited guilds matching the filters are returned.
+    #[view(getGuildsPage)]
+    fn get_guilds_page(
+        &self,
+        cursor: usize,
+        page_size: usize,
+        only_active: bool,
+        min_total_staked: BigUint,
+    ) -> GuildsPage<Self::Api> {
+        let deployed_guilds = self.deployed_guilds();
+        let (start, end) = self.get_page_bounds(cursor, page_size, deployed_guilds.len());
+
+        let mut guilds = ManagedVec::new();
+        for index in start..end {
+            let guild_id = deployed_guilds.get_by_index(index + 1);
+            let opt_guild = self.guild_ids().get_address(guild_id);
+            let guild_master_id = self.guild_master_for_guild(guild_id).get();
+            let opt_guild_master = self.user_ids().get_address(guild_master_id);
+            require!(
+                opt_guild.is_some() && opt_guild_master.is_some(),
+                "Invalid setup"
+            );
+
+            let guild = unsafe { opt_guild.unwrap_unchecked() };
+            let summary: GuildSummary<Self::Api> = self
+                .guild_proxy()
+                .contract(guild.clone())
+                .get_guild_summary()
+                .execute_on_dest_context();
+            if (only_active && !summary.active) || summary.total_staked < min_total_staked {
+                continue;
+            }
+
+            guilds.push(GuildSummaryResultType {
+                guild,
+                guild_master: unsafe { opt_guild_master.unwrap_unchecked() },
+                summary,
+            });
+        }
+
+        GuildsPage {
+            guilds,
+            next_cursor: self.get_next_cursor(end, deployed_guilds.len()),
+        }
+    }
+
+    #[view(getClosedGuildsPage)]
+    fn get_closed_guilds_page(
+        &self,
+        cursor: usize,
+        page_size: usize,
+    ) -> ClosedGuildsPage<Self::Api> {
+        let closed_guilds = self.closed_guilds();
+        let (start, end) = self.get_page_bounds(cursor, page_size, closed_guilds.len());
+
+        let mut guilds = ManagedVec::new();
+        for index in start..end {
+            guilds.push(closed_guilds.get_by_index(index + 1));
+        }
+
+        ClosedGuildsPage {
+            guilds,
+            next_cursor: self.get_next_cursor(end, closed_guilds.len()),
+        }
+    }
+
+    fn get_page_bounds(&self, cursor: usize, page_size: usize, total: usize) -> (usize, usize) {
+        require!(
+            page_size > 0 && page_size <= MAX_PAGE_SIZE,
+            "Invalid page size"
+        );
+
+        let start = core::cmp::min(cursor, total);
+        let end = core::cmp::min(start + page_size, total);
+
+        (start, end)
+    }
+
+    fn get_next_cursor(&self, end: usize, total: usize) -> Option<usize> {
+        if end < total {
+            Some(end)
+        } else {
+            None
+        }
+    }
+}
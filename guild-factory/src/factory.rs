@@ -6,6 +6,7 @@ multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
 static UNKNOWN_GUILD_ERR_MSG: &[u8] = b"Unknown guild";
+pub static INVALID_REWARD_TOKEN_ERR_MSG: &[u8] = b"Invalid reward token";
 
 #[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
 pub struct GuildLocalConfig<M: ManagedTypeApi> {
@@ -21,8 +22,9 @@ pub struct GetGuildResultType<M: ManagedTypeApi> {
 
 #[multiversx_sc::module]
 pub trait FactoryModule: crate::config::ConfigModule {
+    /// The guild pays rewards in the farming token, unless another allowed reward token is given
     #[endpoint(deployGuild)]
-    fn deploy_guild(&self) -> ManagedAddress {
+    fn deploy_guild(&self, opt_reward_token_id: OptionalValue<TokenIdentifier>) -> ManagedAddress {
         let caller = self.blockchain().get_caller();
         let caller_id = self.user_ids().get_id_or_insert(&caller);
         let guild_mapper = self.guild_sc_for_user(caller_id);
@@ -34,6 +36,15 @@ pub trait FactoryModule: crate::config::ConfigModule {
         self.require_config_setup_complete();
 
         let guild_config = self.guild_local_config().get();
+        let reward_token_id = match opt_reward_token_id {
+            OptionalValue::Some(token_id) => {
+                self.require_allowed_reward_token(&token_id);
+
+                token_id
+            }
+            OptionalValue::None => guild_config.farming_token_id.clone(),
+        };
+
         let config_sc_address = config_sc_mapper.get();
         let source_address = self.guild_sc_source_address().get();
         let code_metadata = self.get_default_code_metadata();
@@ -41,6 +52,7 @@ pub trait FactoryModule: crate::config::ConfigModule {
             .guild_proxy()
             .init(
                 guild_config.farming_token_id,
+                reward_token_id,
                 guild_config.division_safety_constant,
                 config_sc_address,
                 caller,
@@ -50,6 +62,8 @@ pub trait FactoryModule: crate::config::ConfigModule {
 
         let guild_id = self.guild_ids().insert_new(&guild_address);
         let _ = self.deployed_guilds().insert(guild_id);
+        let current_code_version = self.current_guild_code_version().get();
+        self.guild_code_version(guild_id).set(current_code_version);
         self.guild_master_for_guild(guild_id).set(caller_id);
         guild_mapper.set(guild_id);
 
@@ -69,7 +83,8 @@ pub trait FactoryModule: crate::config::ConfigModule {
         self.require_guild_setup_complete(guild.clone());
 
         self.resume_guild(guild.clone());
-        self.start_produce_rewards(guild);
+        self.start_produce_rewards(guild.clone());
+        self.register_guild_farm_token(guild_id, guild);
     }
 
     #[view(getAllGuilds)]
@@ -100,14 +115,39 @@ pub trait FactoryModule: crate::config::ConfigModule {
         self.guild_ids().get_id_non_zero(&guild_address)
     }
 
+    /// Rewards accrued by the guild which the factory could not fund yet.
+    /// Closed guilds keep their deficit until it is settled.
+    #[view(getGuildRewardsDeficit)]
+    fn get_guild_rewards_deficit(&self, guild: ManagedAddress) -> BigUint {
+        let guild_id = self.guild_ids().get_id(&guild);
+        if guild_id == 0 {
+            return self.closed_guild_rewards_deficit(&guild).get();
+        }
+
+        self.guild_rewards_deficit(guild_id).get()
+    }
+
     fn remove_guild_common(&self, guild: ManagedAddress) {
+        let farm_token_id = self.external_farm_token_id(guild.clone()).get();
+        self.guild_for_farm_token(&farm_token_id).clear();
+
         let guild_master = self.external_guild_master_address(guild.clone()).get();
         let guild_id = self.guild_ids().remove_by_address(&guild);
+
+        // the closed guild may still settle its deficit
+        let deficit = self.guild_rewards_deficit(guild_id).take();
+        self.closed_guild_rewards_deficit(&guild).set(deficit);
         let user_id = self.user_ids().remove_by_address(&guild_master);
 
         let removed = self.deployed_guilds().swap_remove(&guild_id);
         require!(removed, UNKNOWN_GUILD_ERR_MSG);
 
+        let code_version = self.guild_code_version(guild_id).take();
+        if code_version < self.current_guild_code_version().get() {
+            self.guilds_pending_upgrade()
+                .update(|pending| *pending -= 1);
+        }
+
         let mapper = self.guild_sc_for_user(user_id);
         require!(!mapper.is_empty(), "Unknown guild master");
 
@@ -116,6 +156,12 @@ pub trait FactoryModule: crate::config::ConfigModule {
         self.guild_master_for_guild(guild_id).clear();
     }
 
+    /// Lets the router find the guild of each farm token
+    fn register_guild_farm_token(&self, guild_id: AddressId, guild: ManagedAddress) {
+        let farm_token_id = self.external_farm_token_id(guild).get();
+        self.guild_for_farm_token(&farm_token_id).set(guild_id);
+    }
+
     fn require_known_guild(&self, guild_id: AddressId) {
         require!(
             self.deployed_guilds().contains(&guild_id),
@@ -123,6 +169,15 @@ pub trait FactoryModule: crate::config::ConfigModule {
         );
     }
 
+    /// The farming token is always allowed
+    fn require_allowed_reward_token(&self, token_id: &TokenIdentifier) {
+        let guild_config = self.guild_local_config().get();
+        require!(
+            token_id == &guild_config.farming_token_id || self.reward_tokens().contains(token_id),
+            INVALID_REWARD_TOKEN_ERR_MSG
+        );
+    }
+
     fn require_guild_master_caller(&self, guild_id: AddressId, caller_id: AddressId) {
         let guild_master_id = self.guild_master_for_guild(guild_id).get();
         require!(
@@ -180,12 +235,42 @@ pub trait FactoryModule: crate::config::ConfigModule {
     #[storage_mapper("guildScForUser")]
     fn guild_sc_for_user(&self, user_id: AddressId) -> SingleValueMapper<AddressId>;
 
+    #[view(getCurrentGuildCodeVersion)]
+    #[storage_mapper("currentGuildCodeVersion")]
+    fn current_guild_code_version(&self) -> SingleValueMapper<u32>;
+
+    #[view(getGuildCodeVersion)]
+    #[storage_mapper("guildCodeVersion")]
+    fn guild_code_version(&self, guild_id: AddressId) -> SingleValueMapper<u32>;
+
+    #[view(getGuildsPendingUpgrade)]
+    #[storage_mapper("guildsPendingUpgrade")]
+    fn guilds_pending_upgrade(&self) -> SingleValueMapper<usize>;
+
     #[storage_mapper("guildMasterForGuild")]
     fn guild_master_for_guild(&self, guild_id: AddressId) -> SingleValueMapper<AddressId>;
 
     #[view(getRemainingRewards)]
     #[storage_mapper("remainingRewards")]
-    fn remaining_rewards(&self) -> SingleValueMapper<BigUint>;
+    fn remaining_rewards(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("guildRewardsDeficit")]
+    fn guild_rewards_deficit(&self, guild_id: AddressId) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("closedGuildRewardsDeficit")]
+    fn closed_guild_rewards_deficit(&self, guild: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalRewardsDeficit)]
+    #[storage_mapper("totalRewardsDeficit")]
+    fn total_rewards_deficit(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[view(getRewardTokens)]
+    #[storage_mapper("rewardTokens")]
+    fn reward_tokens(&self) -> UnorderedSetMapper<TokenIdentifier>;
+
+    #[storage_mapper("guildForFarmToken")]
+    fn guild_for_farm_token(&self, farm_token_id: &TokenIdentifier)
+        -> SingleValueMapper<AddressId>;
 
     #[storage_mapper("userIds")]
     fn user_ids(&self) -> AddressToIdMapper<Self::Api>;
@@ -201,6 +286,18 @@ pub trait FactoryModule: crate::config::ConfigModule {
         sc_addr: ManagedAddress,
     ) -> SingleValueMapper<ManagedAddress, ManagedAddress>;
 
+    #[storage_mapper_from_address("reward_token_id")]
+    fn external_reward_token_id(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<TokenIdentifier, ManagedAddress>;
+
+    #[storage_mapper_from_address("farm_token_id")]
+    fn external_farm_token_id(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<TokenIdentifier, ManagedAddress>;
+
     #[storage_mapper_from_address("guildMasterTiers")]
     fn external_guild_master_tiers(
         &self,
@@ -4,34 +4,93 @@ multiversx_sc::imports!();
 
 static INVALID_PAYMENT_ERR_MSG: &[u8] = b"Invalid payment";
 
-pub const BASE_REWARD_MULTIPLIER: u32 = 10;
-
 #[multiversx_sc::module]
 pub trait GuildInteractionsModule:
     crate::factory::FactoryModule
     + crate::config::ConfigModule
+    + crate::reward_budget::RewardBudgetModule
     + multiversx_sc_modules::only_admin::OnlyAdminModule
+    + utils::UtilsModule
 {
+    /// The part of the amount that can't be funded is recorded as the guild's deficit,
+    /// including the amounts above the guild's epoch allowance.
+    /// Deficits are settled first, so other guilds may not use the rewards reserved for them.
+    /// Closed guilds may only request the rewards for their remaining deficit.
     #[endpoint(requestRewards)]
     fn request_rewards(&self, amount: BigUint) -> BigUint {
         let caller = self.blockchain().get_caller();
+        if self.closed_guilds().contains(&caller) {
+            return self.request_closed_guild_rewards(caller, amount);
+        }
+
         let caller_id = self.guild_ids().get_id_non_zero(&caller);
         self.require_known_guild(caller_id);
 
-        let mut total_request = amount * BASE_REWARD_MULTIPLIER;
-        self.remaining_rewards().update(|rew| {
-            total_request = core::cmp::min(total_request.clone(), (*rew).clone());
-            *rew -= &total_request;
-        });
+        let reward_token_id = self.external_reward_token_id(caller.clone()).get();
+        let deficit_mapper = self.guild_rewards_deficit(caller_id);
+        let total_deficit_mapper = self.total_rewards_deficit(&reward_token_id);
+        let other_guilds_deficit = total_deficit_mapper.get() - deficit_mapper.get();
 
-        let guild_config = self.guild_local_config().get();
-        let reward_payment = EsdtTokenPayment::new(guild_config.farming_token_id, 0, total_request);
+        let remaining_rewards_mapper = self.remaining_rewards(&reward_token_id);
+        let remaining_rewards = remaining_rewards_mapper.get();
+        let available_rewards = if remaining_rewards > other_guilds_deficit {
+            &remaining_rewards - &other_guilds_deficit
+        } else {
+            BigUint::zero()
+        };
+        let multiplier = self.get_reward_request_multiplier();
+        let mut total_request = core::cmp::min(amount.clone() * multiplier, available_rewards);
+        if let Some(allowance) = self.get_remaining_allowance(caller_id) {
+            total_request = core::cmp::min(total_request, allowance);
+        }
+        remaining_rewards_mapper.set(&remaining_rewards - &total_request);
+        self.consume_reward_budget(caller_id, &total_request);
+
+        let new_deficit = if amount > total_request {
+            &amount - &total_request
+        } else {
+            BigUint::zero()
+        };
+        total_deficit_mapper.set(&other_guilds_deficit + &new_deficit);
+        deficit_mapper.set(new_deficit);
+
+        let reward_payment = EsdtTokenPayment::new(reward_token_id, 0, total_request);
         self.send()
             .direct_non_zero_esdt_payment(&caller, &reward_payment);
 
         reward_payment.amount
     }
 
+    /// Closed guilds only settle the rewards accrued before closing, without any multiplier
+    fn request_closed_guild_rewards(&self, guild: ManagedAddress, amount: BigUint) -> BigUint {
+        let reward_token_id = self.external_reward_token_id(guild.clone()).get();
+        let deficit_mapper = self.closed_guild_rewards_deficit(&guild);
+        let deficit = deficit_mapper.get();
+        let total_deficit_mapper = self.total_rewards_deficit(&reward_token_id);
+        let other_guilds_deficit = total_deficit_mapper.get() - &deficit;
+
+        let remaining_rewards_mapper = self.remaining_rewards(&reward_token_id);
+        let remaining_rewards = remaining_rewards_mapper.get();
+        let available_rewards = if remaining_rewards > other_guilds_deficit {
+            &remaining_rewards - &other_guilds_deficit
+        } else {
+            BigUint::zero()
+        };
+        let requested_amount = core::cmp::min(amount, deficit);
+        let total_request = core::cmp::min(requested_amount.clone(), available_rewards);
+        remaining_rewards_mapper.set(&remaining_rewards - &total_request);
+
+        let new_deficit = &requested_amount - &total_request;
+        total_deficit_mapper.set(&other_guilds_deficit + &new_deficit);
+        deficit_mapper.set(new_deficit);
+
+        let reward_payment = EsdtTokenPayment::new(reward_token_id, 0, total_request);
+        self.send()
+            .direct_non_zero_esdt_payment(&guild, &reward_payment);
+
+        reward_payment.amount
+    }
+
     #[payable("*")]
     #[endpoint(migrateToOtherGuild)]
     fn migrate_to_other_guild(&self, guild: ManagedAddress, original_caller: ManagedAddress) {
@@ -43,7 +102,33 @@ pub trait GuildInteractionsModule:
         let payment = self.check_payment_is_farming_token();
         let _: EsdtTokenPayment = self
             .guild_sc_proxy(guild)
-            .stake_farm_endpoint(OptionalValue::Some(original_caller))
+            .stake_farm_endpoint(
+                OptionalValue::Some(original_caller),
+                OptionalValue::<ManagedAddress>::None,
+            )
+            .with_esdt_transfer(payment)
+            .execute_on_dest_context();
+    }
+
+    /// Rewards claimed in a guild, staked in another guild on behalf of the user
+    #[payable("*")]
+    #[endpoint(stakeRewardsInGuild)]
+    fn stake_rewards_in_guild(&self, guild: ManagedAddress, original_caller: ManagedAddress) {
+        let caller = self.blockchain().get_caller();
+        let caller_id = self.guild_ids().get_id_non_zero(&caller);
+        self.require_known_guild(caller_id);
+        require!(caller != guild, "Invalid guild");
+
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+        self.require_known_guild(guild_id);
+
+        let payment = self.check_payment_is_farming_token();
+        let _: EsdtTokenPayment = self
+            .guild_sc_proxy(guild)
+            .stake_farm_endpoint(
+                OptionalValue::Some(original_caller),
+                OptionalValue::<ManagedAddress>::None,
+            )
             .with_esdt_transfer(payment)
             .execute_on_dest_context();
     }
@@ -55,12 +140,28 @@ pub trait GuildInteractionsModule:
         let caller_id = self.guild_ids().get_id_non_zero(&caller);
         self.require_known_guild(caller_id);
 
-        self.deposit_rewards_common();
+        let (token_id, amount) = self.call_value().single_fungible_esdt();
+        let reward_token_id = self.external_reward_token_id(caller.clone()).get();
+        require!(token_id == reward_token_id, INVALID_PAYMENT_ERR_MSG);
+
+        self.deposit_rewards_common(&token_id, amount);
 
         self.remove_guild_common(caller.clone());
         let _ = self.closed_guilds().insert(caller);
     }
 
+    /// Fees collected in the farming token by guilds paying rewards in other tokens
+    #[payable("*")]
+    #[endpoint(depositGuildFees)]
+    fn deposit_guild_fees(&self) {
+        let caller = self.blockchain().get_caller();
+        let caller_id = self.guild_ids().get_id_non_zero(&caller);
+        self.require_known_guild(caller_id);
+
+        let payment = self.check_payment_is_farming_token();
+        self.deposit_rewards_common(&payment.token_identifier, payment.amount);
+    }
+
     #[endpoint(closeGuildNoRewardsRemaining)]
     fn close_guild_no_rewards_remaining(&self) {
         let caller = self.blockchain().get_caller();
@@ -71,18 +172,63 @@ pub trait GuildInteractionsModule:
         let _ = self.closed_guilds().insert(caller);
     }
 
+    #[endpoint(changeGuildMaster)]
+    fn change_guild_master(&self, new_guild_master: ManagedAddress) {
+        let caller = self.blockchain().get_caller();
+        let guild_id = self.guild_ids().get_id_non_zero(&caller);
+        self.require_known_guild(guild_id);
+
+        let new_guild_master_id = self.user_ids().get_id_or_insert(&new_guild_master);
+        let new_guild_master_mapper = self.guild_sc_for_user(new_guild_master_id);
+        require!(
+            new_guild_master_mapper.is_empty(),
+            "New guild master already has a guild deployed"
+        );
+
+        let guild_master_mapper = self.guild_master_for_guild(guild_id);
+        let old_guild_master_id = guild_master_mapper.get();
+        self.guild_sc_for_user(old_guild_master_id).clear();
+
+        new_guild_master_mapper.set(guild_id);
+        guild_master_mapper.set(new_guild_master_id);
+    }
+
     #[only_admin]
     #[payable("*")]
     #[endpoint(depositRewardsAdmins)]
     fn deposit_rewards_admins(&self) {
-        self.deposit_rewards_common();
+        let (token_id, amount) = self.call_value().single_fungible_esdt();
+        self.require_allowed_reward_token(&token_id);
+
+        self.deposit_rewards_common(&token_id, amount);
     }
 
-    fn deposit_rewards_common(&self) {
-        // Farming token is the same as reward token in farm staking
-        let payment = self.check_payment_is_farming_token();
-        self.remaining_rewards()
-            .update(|rew| *rew += payment.amount);
+    /// Guilds may then be deployed paying rewards in these tokens
+    #[only_admin]
+    #[endpoint(addRewardTokens)]
+    fn add_reward_tokens(&self, token_ids: MultiValueEncoded<TokenIdentifier>) {
+        let mut reward_tokens_mapper = self.reward_tokens();
+        for token_id in token_ids {
+            self.require_valid_token_id(&token_id);
+
+            let _ = reward_tokens_mapper.insert(token_id);
+        }
+    }
+
+    /// Already deployed guilds keep their reward token
+    #[only_admin]
+    #[endpoint(removeRewardTokens)]
+    fn remove_reward_tokens(&self, token_ids: MultiValueEncoded<TokenIdentifier>) {
+        let mut reward_tokens_mapper = self.reward_tokens();
+        for token_id in token_ids {
+            let _ = reward_tokens_mapper.swap_remove(&token_id);
+        }
+    }
+
+    /// The rewards pool is kept separately for each reward token
+    fn deposit_rewards_common(&self, token_id: &TokenIdentifier, amount: BigUint) {
+        self.remaining_rewards(token_id)
+            .update(|rew| *rew += amount);
     }
 
     fn check_payment_is_farming_token(&self) -> EsdtTokenPayment {
@@ -0,0 +1,62 @@
+use guild_sc::guild_metadata::GuildMetadata;
+
+multiversx_sc::imports!();
+
+/// Guild masters set the metadata in their guild. Admins may hide it, e.g. for abusive content.
+#[multiversx_sc::module]
+pub trait GuildMetadataModule:
+    crate::factory::FactoryModule
+    + crate::config::ConfigModule
+    + multiversx_sc_modules::only_admin::OnlyAdminModule
+{
+    #[only_admin]
+    #[endpoint(hideGuildMetadata)]
+    fn hide_guild_metadata(&self, guild: ManagedAddress) {
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+        self.require_known_guild(guild_id);
+
+        self.guild_metadata_hidden(guild_id).set(true);
+    }
+
+    #[only_admin]
+    #[endpoint(unhideGuildMetadata)]
+    fn unhide_guild_metadata(&self, guild: ManagedAddress) {
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+
+        self.guild_metadata_hidden(guild_id).clear();
+    }
+
+    /// None if the guild master did not set any metadata, or if it was hidden
+    #[view(getGuildMetadata)]
+    fn get_guild_metadata(&self, guild: ManagedAddress) -> Option<GuildMetadata<Self::Api>> {
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+        self.require_known_guild(guild_id);
+
+        if self.guild_metadata_hidden(guild_id).get() {
+            return None;
+        }
+
+        let metadata_mapper = self.external_guild_metadata(guild);
+        if metadata_mapper.is_empty() {
+            return None;
+        }
+
+        Some(metadata_mapper.get())
+    }
+
+    #[view(isGuildMetadataHidden)]
+    fn is_guild_metadata_hidden(&self, guild: ManagedAddress) -> bool {
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+
+        self.guild_metadata_hidden(guild_id).get()
+    }
+
+    #[storage_mapper("guildMetadataHidden")]
+    fn guild_metadata_hidden(&self, guild_id: AddressId) -> SingleValueMapper<bool>;
+
+    #[storage_mapper_from_address("guildMetadata")]
+    fn external_guild_metadata(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<GuildMetadata<Self::Api>, ManagedAddress>;
+}
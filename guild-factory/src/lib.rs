@@ -6,8 +6,13 @@ use factory::GuildLocalConfig;
 multiversx_sc::imports!();
 
 pub mod config;
+pub mod discovery;
 pub mod factory;
 pub mod guild_interactions;
+pub mod guild_metadata;
+pub mod reward_budget;
+pub mod router;
+pub mod upgrade;
 
 const MIN_DIV_SAFETY: u64 = 1_000_000_000_000_000_000;
 
@@ -16,8 +21,13 @@ pub trait GuildFactory:
     config::ConfigModule
     + factory::FactoryModule
     + guild_interactions::GuildInteractionsModule
+    + guild_metadata::GuildMetadataModule
+    + reward_budget::RewardBudgetModule
+    + router::RouterModule
+    + discovery::DiscoveryModule
     + multiversx_sc_modules::only_admin::OnlyAdminModule
     + utils::UtilsModule
+    + upgrade::UpgradeModule
 {
     #[init]
     fn init(
@@ -45,5 +55,16 @@ pub trait GuildFactory:
     }
 
     #[upgrade]
-    fn upgrade(&self) {}
+    fn upgrade(&self) {
+        // the rewards pool used to hold only the farming token
+        let legacy_remaining_rewards = self.legacy_remaining_rewards().take();
+        if legacy_remaining_rewards > 0 {
+            let guild_config = self.guild_local_config().get();
+            self.remaining_rewards(&guild_config.farming_token_id)
+                .update(|rew| *rew += legacy_remaining_rewards);
+        }
+    }
+
+    #[storage_mapper("remainingRewards")]
+    fn legacy_remaining_rewards(&self) -> SingleValueMapper<BigUint>;
 }
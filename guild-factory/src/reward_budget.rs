@@ -0,0 +1,156 @@
+use common_structs::Epoch;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+pub const DEFAULT_REWARD_MULTIPLIER: u32 = 10;
+
+#[derive(TypeAbi, TopEncode, TopDecode)]
+pub struct EpochConsumption<M: ManagedTypeApi> {
+    pub epoch: Epoch,
+    pub amount: BigUint<M>,
+}
+
+/// Limits how much each guild may pull from the shared rewards pool per epoch.
+/// Guilds without an allowance of their own use the default one, if any.
+#[multiversx_sc::module]
+pub trait RewardBudgetModule:
+    crate::factory::FactoryModule
+    + multiversx_sc_modules::only_admin::OnlyAdminModule
+    + utils::UtilsModule
+{
+    /// Guilds receive the requested amount times the multiplier, to keep a reserve of rewards
+    #[only_admin]
+    #[endpoint(setRewardRequestMultiplier)]
+    fn set_reward_request_multiplier(&self, multiplier: u32) {
+        require!(multiplier > 0, "Invalid multiplier");
+
+        self.reward_request_multiplier().set(multiplier);
+    }
+
+    /// No argument means guilds have no default limit
+    #[only_admin]
+    #[endpoint(setDefaultGuildEpochAllowance)]
+    fn set_default_guild_epoch_allowance(&self, opt_allowance: OptionalValue<BigUint>) {
+        let mapper = self.default_guild_epoch_allowance();
+        match opt_allowance {
+            OptionalValue::Some(allowance) => mapper.set(allowance),
+            OptionalValue::None => mapper.clear(),
+        }
+    }
+
+    /// No argument means the guild uses the default allowance
+    #[only_admin]
+    #[endpoint(setGuildEpochAllowance)]
+    fn set_guild_epoch_allowance(
+        &self,
+        guild: ManagedAddress,
+        opt_allowance: OptionalValue<BigUint>,
+    ) {
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+        self.require_known_guild(guild_id);
+
+        let mapper = self.guild_epoch_allowance(guild_id);
+        match opt_allowance {
+            OptionalValue::Some(allowance) => mapper.set(allowance),
+            OptionalValue::None => mapper.clear(),
+        }
+    }
+
+    #[view(getRewardRequestMultiplier)]
+    fn get_reward_request_multiplier(&self) -> u32 {
+        let mapper = self.reward_request_multiplier();
+        if mapper.is_empty() {
+            return DEFAULT_REWARD_MULTIPLIER;
+        }
+
+        mapper.get()
+    }
+
+    /// Total rewards sent to the guild
+    #[view(getGuildConsumedRewards)]
+    fn get_guild_consumed_rewards(&self, guild: ManagedAddress) -> BigUint {
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+
+        self.guild_consumed_rewards(guild_id).get()
+    }
+
+    /// Rewards the guild may still request in the current epoch. None means no limit.
+    #[view(getGuildRemainingAllowance)]
+    fn get_guild_remaining_allowance(&self, guild: ManagedAddress) -> Option<BigUint> {
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+
+        self.get_remaining_allowance(guild_id)
+    }
+
+    fn get_remaining_allowance(&self, guild_id: AddressId) -> Option<BigUint> {
+        let guild_allowance_mapper = self.guild_epoch_allowance(guild_id);
+        let allowance = if !guild_allowance_mapper.is_empty() {
+            guild_allowance_mapper.get()
+        } else {
+            let default_allowance_mapper = self.default_guild_epoch_allowance();
+            if default_allowance_mapper.is_empty() {
+                return None;
+            }
+
+            default_allowance_mapper.get()
+        };
+
+        let consumed = self.get_consumed_this_epoch(guild_id);
+        if allowance > consumed {
+            Some(allowance - consumed)
+        } else {
+            Some(BigUint::zero())
+        }
+    }
+
+    fn get_consumed_this_epoch(&self, guild_id: AddressId) -> BigUint {
+        let consumption_mapper = self.guild_epoch_consumption(guild_id);
+        if consumption_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        let consumption = consumption_mapper.get();
+        let current_epoch = self.blockchain().get_block_epoch();
+        if consumption.epoch == current_epoch {
+            consumption.amount
+        } else {
+            BigUint::zero()
+        }
+    }
+
+    fn consume_reward_budget(&self, guild_id: AddressId, amount: &BigUint) {
+        if *amount == 0 {
+            return;
+        }
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let consumed = self.get_consumed_this_epoch(guild_id);
+        self.guild_epoch_consumption(guild_id)
+            .set(EpochConsumption {
+                epoch: current_epoch,
+                amount: consumed + amount,
+            });
+        self.guild_consumed_rewards(guild_id)
+            .update(|total| *total += amount);
+    }
+
+    #[storage_mapper("rewardRequestMultiplier")]
+    fn reward_request_multiplier(&self) -> SingleValueMapper<u32>;
+
+    #[view(getDefaultGuildEpochAllowance)]
+    #[storage_mapper("defaultGuildEpochAllowance")]
+    fn default_guild_epoch_allowance(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("guildEpochAllowance")]
+    fn guild_epoch_allowance(&self, guild_id: AddressId) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("guildEpochConsumption")]
+    fn guild_epoch_consumption(
+        &self,
+        guild_id: AddressId,
+    ) -> SingleValueMapper<EpochConsumption<Self::Api>>;
+
+    #[storage_mapper("guildConsumedRewards")]
+    fn guild_consumed_rewards(&self, guild_id: AddressId) -> SingleValueMapper<BigUint>;
+}
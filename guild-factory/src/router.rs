@@ -0,0 +1,132 @@
+use common_structs::PaymentsVec;
+use guild_sc::user_actions::relayed_actions::ProxyTrait as _;
+
+multiversx_sc::imports!();
+
+pub type GuildActionResultMultiValue<M> =
+    MultiValue3<ManagedAddress<M>, EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+pub type GuildCompoundResultMultiValue<M> = MultiValue2<ManagedAddress<M>, EsdtTokenPayment<M>>;
+
+/// Claims, compounds or unstakes positions from several guilds in one transaction.
+/// The farm tokens are grouped by guild, and each guild sends the outputs straight to the user.
+#[multiversx_sc::module]
+pub trait RouterModule:
+    crate::factory::FactoryModule
+    + crate::config::ConfigModule
+    + crate::guild_interactions::GuildInteractionsModule
+    + crate::reward_budget::RewardBudgetModule
+    + multiversx_sc_modules::only_admin::OnlyAdminModule
+    + utils::UtilsModule
+{
+    /// For guilds resumed before the farm token registry existed
+    #[endpoint(registerGuildFarmToken)]
+    fn register_guild_farm_token_endpoint(&self, guild: ManagedAddress) {
+        let guild_id = self.guild_ids().get_id_non_zero(&guild);
+        self.require_known_guild(guild_id);
+
+        self.register_guild_farm_token(guild_id, guild);
+    }
+
+    /// Results are (guild, new_farm_token, rewards) for each guild
+    #[payable("*")]
+    #[endpoint(claimRewardsMultiGuild)]
+    fn claim_rewards_multi_guild(
+        &self,
+    ) -> MultiValueEncoded<GuildActionResultMultiValue<Self::Api>> {
+        let caller = self.blockchain().get_caller();
+        let mut result = MultiValueEncoded::new();
+        for payments in self.group_payments_by_farm_token() {
+            let guild = self.get_guild_for_farm_token(payments.get(0).token_identifier.clone());
+            let claim_result: MultiValue2<EsdtTokenPayment, EsdtTokenPayment> = self
+                .guild_sc_proxy(guild.clone())
+                .relayed_claim_rewards(caller.clone())
+                .with_multi_token_transfer(payments)
+                .execute_on_dest_context();
+            let (new_farm_token, rewards) = claim_result.into_tuple();
+
+            result.push((guild, new_farm_token, rewards).into());
+        }
+
+        result
+    }
+
+    /// Results are (guild, new_farm_token) for each guild
+    #[payable("*")]
+    #[endpoint(compoundRewardsMultiGuild)]
+    fn compound_rewards_multi_guild(
+        &self,
+    ) -> MultiValueEncoded<GuildCompoundResultMultiValue<Self::Api>> {
+        let caller = self.blockchain().get_caller();
+        let mut result = MultiValueEncoded::new();
+        for payments in self.group_payments_by_farm_token() {
+            let guild = self.get_guild_for_farm_token(payments.get(0).token_identifier.clone());
+            let new_farm_token: EsdtTokenPayment = self
+                .guild_sc_proxy(guild.clone())
+                .relayed_compound_rewards(caller.clone())
+                .with_multi_token_transfer(payments)
+                .execute_on_dest_context();
+
+            result.push((guild, new_farm_token).into());
+        }
+
+        result
+    }
+
+    /// Results are (guild, unbond_token, rewards) for each guild
+    #[payable("*")]
+    #[endpoint(unstakeFarmMultiGuild)]
+    fn unstake_farm_multi_guild(
+        &self,
+    ) -> MultiValueEncoded<GuildActionResultMultiValue<Self::Api>> {
+        let caller = self.blockchain().get_caller();
+        let mut result = MultiValueEncoded::new();
+        for payments in self.group_payments_by_farm_token() {
+            let guild = self.get_guild_for_farm_token(payments.get(0).token_identifier.clone());
+            let unstake_result: MultiValue2<EsdtTokenPayment, EsdtTokenPayment> = self
+                .guild_sc_proxy(guild.clone())
+                .relayed_unstake_farm(caller.clone())
+                .with_multi_token_transfer(payments)
+                .execute_on_dest_context();
+            let (unbond_token, rewards) = unstake_result.into_tuple();
+
+            result.push((guild, unbond_token, rewards).into());
+        }
+
+        result
+    }
+
+    #[view(getGuildForFarmToken)]
+    fn get_guild_for_farm_token(&self, farm_token_id: TokenIdentifier) -> ManagedAddress {
+        let guild_id = self.guild_for_farm_token(&farm_token_id).get();
+        let opt_guild = self.guild_ids().get_address(guild_id);
+        require!(opt_guild.is_some(), "Unknown farm token");
+
+        unsafe { opt_guild.unwrap_unchecked() }
+    }
+
+    /// Payments of the same farm token are kept in the order they were received
+    fn group_payments_by_farm_token(&self) -> ManagedVec<PaymentsVec<Self::Api>> {
+        let payments = self.get_non_empty_payments();
+        let mut groups = ManagedVec::<Self::Api, PaymentsVec<Self::Api>>::new();
+        for (i, payment) in payments.iter().enumerate() {
+            let already_grouped = payments
+                .iter()
+                .take(i)
+                .any(|prev_payment| prev_payment.token_identifier == payment.token_identifier);
+            if already_grouped {
+                continue;
+            }
+
+            let mut group = PaymentsVec::new();
+            for other_payment in payments.iter().skip(i) {
+                if other_payment.token_identifier == payment.token_identifier {
+                    group.push(other_payment);
+                }
+            }
+
+            groups.push(group);
+        }
+
+        groups
+    }
+}
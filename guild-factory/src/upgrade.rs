@@ -0,0 +1,79 @@
+multiversx_sc::imports!();
+
+pub const GAS_PER_GUILD_UPGRADE: u64 = 20_000_000;
+
+#[multiversx_sc::module]
+pub trait UpgradeModule:
+    crate::factory::FactoryModule
+    + crate::config::ConfigModule
+    + multiversx_sc_modules::only_admin::OnlyAdminModule
+    + utils::UtilsModule
+{
+    /// New guilds are deployed from the new source. Existing guilds are upgraded through `upgradeGuilds`.
+    #[only_admin]
+    #[endpoint(setGuildSourceAddress)]
+    fn set_guild_source_address(&self, guild_sc_source_address: ManagedAddress) {
+        self.require_sc_address(&guild_sc_source_address);
+
+        self.guild_sc_source_address().set(guild_sc_source_address);
+        self.current_guild_code_version()
+            .update(|version| *version += 1);
+        self.guilds_pending_upgrade()
+            .set(self.deployed_guilds().len());
+        self.upgrade_cursor().set(1);
+    }
+
+    /// Checks at most `max_guilds` deployed guilds, upgrading the outdated ones.
+    /// Returns the number of guilds still pending upgrade.
+    #[only_admin]
+    #[endpoint(upgradeGuilds)]
+    fn upgrade_guilds(&self, max_guilds: usize) -> usize {
+        let current_code_version = self.current_guild_code_version().get();
+        let source_address = self.guild_sc_source_address().get();
+        let code_metadata = self.get_default_code_metadata();
+        let deployed_guilds_mapper = self.deployed_guilds();
+        let pending_mapper = self.guilds_pending_upgrade();
+        let cursor_mapper = self.upgrade_cursor();
+
+        let mut pending = pending_mapper.get();
+        let mut cursor = cursor_mapper.get();
+        let mut checked_guilds = 0;
+        while pending > 0 && checked_guilds < max_guilds {
+            if cursor == 0 || cursor > deployed_guilds_mapper.len() {
+                cursor = 1;
+            }
+
+            let guild_id = deployed_guilds_mapper.get_by_index(cursor);
+            let code_version_mapper = self.guild_code_version(guild_id);
+            if code_version_mapper.get() < current_code_version {
+                let opt_guild_address = self.guild_ids().get_address(guild_id);
+                require!(opt_guild_address.is_some(), "Invalid setup");
+
+                let guild_address = unsafe { opt_guild_address.unwrap_unchecked() };
+                self.send_raw().upgrade_from_source_contract(
+                    &guild_address,
+                    GAS_PER_GUILD_UPGRADE,
+                    &BigUint::zero(),
+                    &source_address,
+                    code_metadata,
+                    &ManagedArgBuffer::new(),
+                );
+
+                code_version_mapper.set(current_code_version);
+                pending -= 1;
+            }
+
+            cursor += 1;
+            checked_guilds += 1;
+        }
+
+        pending_mapper.set(pending);
+        cursor_mapper.set(cursor);
+
+        pending
+    }
+
+    #[view(getUpgradeCursor)]
+    #[storage_mapper("upgradeCursor")]
+    fn upgrade_cursor(&self) -> SingleValueMapper<usize>;
+}
@@ -35,7 +35,8 @@ pub static OTHER_UNBOND_TOKEN_ID: &[u8] = b"UNBOND2-abcdef";
 pub const DIVISION_SAFETY_CONSTANT: u64 = 1_000_000_000_000;
 pub const MIN_UNBOND_EPOCHS: u64 = 5;
 pub const MAX_APR: u64 = 2_500; // 25%
-pub const PER_BLOCK_REWARD_AMOUNT: u64 = 5_000;
+pub const PER_SECOND_REWARD_AMOUNT: u64 = 1_000;
+pub const SECONDS_PER_BLOCK: u64 = 6;
 pub const TOTAL_REWARDS_AMOUNT: u64 = 1_000_000_000_000;
 pub const TOTAL_STAKING_TOKENS_MINTED: u64 = 1_000_000_000_000_000_000;
 
@@ -109,8 +110,7 @@ where
                     base_unbond_token_id: managed_buffer!(b"UNBOND"),
                     base_token_display_name: managed_buffer!(b"DISPLAY"),
                     tokens_decimals: 0,
-                    seconds_per_block: 6,
-                    per_block_reward_amount: managed_biguint!(PER_BLOCK_REWARD_AMOUNT),
+                    per_second_reward_amount: managed_biguint!(PER_SECOND_REWARD_AMOUNT),
                 });
 
                 let mut user_tiers = MultiValueEncoded::new();
@@ -155,7 +155,7 @@ where
 
         b_mock
             .execute_tx(&first_owner_addr, &factory_wrapper, &rust_zero, |sc| {
-                let guild_address = sc.deploy_guild();
+                let guild_address = sc.deploy_guild(OptionalValue::None);
                 assert_eq!(
                     guild_address,
                     managed_address!(first_farm_wrapper.address_ref())
@@ -168,7 +168,7 @@ where
 
         b_mock
             .execute_tx(&second_owner_addr, &factory_wrapper, &rust_zero, |sc| {
-                let guild_address = sc.deploy_guild();
+                let guild_address = sc.deploy_guild(OptionalValue::None);
                 assert_eq!(
                     guild_address,
                     managed_address!(second_farm_wrapper.address_ref())
@@ -296,7 +296,7 @@ where
                 0,
                 &rust_biguint!(1),
                 |sc| {
-                    let _ = sc.stake_farm_endpoint(OptionalValue::None);
+                    let _ = sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
                 },
             )
             .assert_ok();
@@ -315,7 +315,7 @@ where
                 0,
                 &rust_biguint!(1),
                 |sc| {
-                    let _ = sc.stake_farm_endpoint(OptionalValue::None);
+                    let _ = sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
                 },
             )
             .assert_ok();
@@ -350,7 +350,8 @@ where
                 &self.first_farm_wrapper,
                 &payments,
                 |sc| {
-                    let new_farm_token_payment = sc.stake_farm_endpoint(OptionalValue::None);
+                    let new_farm_token_payment =
+                        sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
                     assert_eq!(
                         new_farm_token_payment.token_identifier,
                         managed_token_id!(FARM_TOKEN_ID)
@@ -371,6 +372,7 @@ where
             reward_per_share: managed_biguint!(expected_reward_per_share),
             compounded_reward: managed_biguint!(expected_compounded_reward),
             current_farm_amount: managed_biguint!(expected_total_out_amount),
+            lock: None,
         };
         self.b_mock.check_nft_balance(
             &self.user_address,
@@ -400,7 +402,7 @@ where
                 farm_token_nonce,
                 &rust_biguint!(farm_token_amount),
                 |sc| {
-                    let multi_result = sc.claim_rewards();
+                    let multi_result = sc.claim_rewards(OptionalValue::None);
                     let (first_result, second_result) = multi_result.into_tuple();
 
                     assert_eq!(
@@ -427,6 +429,7 @@ where
             reward_per_share: managed_biguint!(expected_reward_per_share),
             compounded_reward: managed_biguint!(0),
             current_farm_amount: managed_biguint!(farm_token_amount),
+            lock: None,
         };
 
         self.b_mock.check_nft_balance(
@@ -564,8 +567,11 @@ where
             .assert_ok();
     }
 
+    /// Rewards accrue by timestamp, so the block timestamp moves along with the nonce
     pub fn set_block_nonce(&mut self, block_nonce: u64) {
         self.b_mock.set_block_nonce(block_nonce);
+        self.b_mock
+            .set_block_timestamp(block_nonce * SECONDS_PER_BLOCK);
     }
 
     pub fn set_block_epoch(&mut self, block_epoch: u64) {
@@ -2,27 +2,68 @@
 
 pub mod factory_setup;
 
+use energy_factory_mock::EnergyFactoryMock;
 use factory_setup::*;
+use guild_factory::{
+    discovery::DiscoveryModule, factory::FactoryModule,
+    guild_interactions::GuildInteractionsModule,
+    guild_metadata::GuildMetadataModule as FactoryGuildMetadataModule,
+    reward_budget::RewardBudgetModule, router::RouterModule, upgrade::UpgradeModule,
+};
 use guild_sc::{
-    tiered_rewards::total_tokens::TokenPerTierModule,
-    tokens::{request_id::RequestIdModule, token_attributes::StakingFarmTokenAttributes},
+    boosted_yields::BoostedYieldsModule,
+    config::ConfigModule,
+    custom_rewards::{CustomRewardsModule, SECONDS_IN_YEAR},
+    farm_base_impl::base_traits_impl::MAX_CONFIG_SWITCHES_PER_CALL,
+    guild_master_commission::GuildMasterCommissionModule,
+    guild_metadata::{GuildMetadata, GuildMetadataModule},
+    locked_positions::LockedPositionsModule,
+    pending_rewards::PendingRewardsModule,
+    rewards::RewardsModule,
+    tiered_rewards::{read_config::ReadConfigModule, total_tokens::TokenPerTierModule},
+    tokens::{
+        request_id::RequestIdModule,
+        token_attributes::{StakingFarmTokenAttributes, UnbondSftAttributes},
+        unbond_token::UnbondTokenModule,
+    },
     user_actions::{
-        claim_stake_farm_rewards::ClaimStakeFarmRewardsModule,
-        compound_stake_farm_rewards::CompoundStakeFarmRewardsModule, migration::MigrationModule,
-        stake_farm::StakeFarmModule, unbond_farm::UnbondFarmModule,
+        claim_stake_farm_rewards::{ClaimDestination, ClaimStakeFarmRewardsModule},
+        compound_stake_farm_rewards::CompoundStakeFarmRewardsModule,
+        compound_vault::CompoundVaultModule,
+        delegates::{DelegateAction, DelegatesModule},
+        guild_access::{GuildAccessMode, GuildAccessModule},
+        guild_master_handover::GuildMasterHandoverModule,
+        instant_unbond::InstantUnbondModule,
+        lock_position::LockPositionModule,
+        migration::MigrationModule,
+        referrals::ReferralsModule,
+        relayed_actions::RelayedActionsModule,
+        stake_farm::StakeFarmModule,
+        transfer_position::TransferPositionModule,
+        unbond_farm::UnbondFarmModule,
         unstake_farm::UnstakeFarmModule,
     },
     FarmStaking,
 };
-use guild_sc_config::tiers::{TierModule, MAX_PERCENT};
-use multiversx_sc::{codec::Empty, imports::OptionalValue};
+use guild_sc_config::{
+    checkpoints::CheckpointsModule,
+    emission::{EmissionModule, MIN_EMISSION_DECAY_PERIOD_SECONDS},
+    global_config::GlobalConfigModule,
+    lock_options::LockOptionsModule,
+    scheduled_tiers::ScheduledTiersModule,
+    tier_types::{InverseStakedRatioParams, RewardCurve},
+    tiers::{TierModule, MAX_PERCENT},
+    GuildScConfig,
+};
+use multiversx_sc::{
+    codec::Empty,
+    imports::{ManagedBuffer, ManagedVec, MultiValueEncoded, OptionalValue},
+};
 use multiversx_sc_scenario::{
     managed_address, managed_biguint, managed_buffer, managed_token_id, rust_biguint,
     whitebox_legacy::TxTokenTransfer, DebugApi,
 };
 
-pub const BLOCKS_IN_YEAR: u64 = 31_536_000 / 6; // seconds_in_year / 6_seconds_per_block
-
 #[test]
 fn all_setup_test() {
     let _ = FarmStakingSetup::new(
@@ -51,7 +92,7 @@ fn close_guild_test() {
             0,
             &rust_biguint!(farm_in_amount),
             |sc| {
-                sc.stake_farm_endpoint(OptionalValue::None);
+                sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
             },
         )
         .assert_ok();
@@ -90,7 +131,7 @@ fn close_guild_test() {
             0,
             &rust_biguint!(farm_in_amount),
             |sc| {
-                sc.stake_farm_endpoint(OptionalValue::None);
+                sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
             },
         )
         .assert_user_error("Guild closing");
@@ -115,7 +156,7 @@ fn migrate_to_other_guild_test() {
             0,
             &rust_biguint!(farm_in_amount),
             |sc| {
-                sc.stake_farm_endpoint(OptionalValue::None);
+                sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
             },
         )
         .assert_ok();
@@ -163,11 +204,11 @@ fn migrate_to_other_guild_test() {
 
     // check requesting rewards works
 
-    setup.b_mock.set_block_nonce(10);
+    setup.set_block_nonce(10);
     setup.b_mock.set_block_epoch(5);
     setup.b_mock.set_block_epoch(8);
 
-    let expected_reward_token_out = 40;
+    let expected_reward_token_out = 47;
 
     setup
         .b_mock
@@ -178,7 +219,7 @@ fn migrate_to_other_guild_test() {
             2,
             &rust_biguint!(farm_in_amount),
             |sc| {
-                let (_, rewards_payment) = sc.claim_rewards().into_tuple();
+                let (_, rewards_payment) = sc.claim_rewards(OptionalValue::None).into_tuple();
                 assert_eq!(rewards_payment.amount, expected_reward_token_out);
             },
         )
@@ -219,14 +260,14 @@ fn test_unstake_farm() {
     farm_setup.set_block_epoch(current_epoch);
     farm_setup.set_block_nonce(current_block);
 
-    let block_diff = current_block;
-    let expected_rewards_unbounded = block_diff * PER_BLOCK_REWARD_AMOUNT;
+    let elapsed_seconds = current_block * SECONDS_PER_BLOCK;
+    let expected_rewards_unbounded = elapsed_seconds * PER_SECOND_REWARD_AMOUNT;
 
-    // ~= 4 * 10 = 40
+    // ~= 0.79 * 60 = 47
     let expected_rewards_max_apr =
-        farm_in_amount * MAX_APR / MAX_PERCENT / BLOCKS_IN_YEAR * block_diff;
+        farm_in_amount * MAX_APR * elapsed_seconds / MAX_PERCENT / SECONDS_IN_YEAR;
     let expected_rewards = core::cmp::min(expected_rewards_unbounded, expected_rewards_max_apr);
-    assert_eq!(expected_rewards, 40);
+    assert_eq!(expected_rewards, 47);
 
     let expected_ride_token_balance =
         rust_biguint!(USER_TOTAL_RIDE_TOKENS) - farm_in_amount + expected_rewards;
@@ -260,10 +301,10 @@ fn test_claim_rewards() {
     farm_setup.set_block_nonce(10);
 
     // value taken from the "test_unstake_farm" test
-    let expected_reward_token_out = 40;
+    let expected_reward_token_out = 47;
     let expected_farming_token_balance =
         rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + expected_reward_token_out);
-    let expected_reward_per_share = 400_000;
+    let expected_reward_per_share = 470_000;
     farm_setup.claim_rewards(
         farm_in_amount,
         expected_farm_token_nonce,
@@ -295,8 +336,8 @@ fn compound_rewards_test() {
     farm_setup.set_block_nonce(10);
 
     // value taken from the "test_unstake_farm" test
-    let expected_reward_token_out = 40;
-    let expected_reward_per_share = 400_000;
+    let expected_reward_token_out = 47;
+    let expected_reward_per_share = 470_000;
 
     farm_setup
         .b_mock
@@ -316,6 +357,7 @@ fn compound_rewards_test() {
         reward_per_share: managed_biguint!(expected_reward_per_share),
         compounded_reward: managed_biguint!(expected_reward_token_out),
         current_farm_amount: managed_biguint!(farm_in_amount + expected_reward_token_out),
+        lock: None,
     };
 
     farm_setup.b_mock.check_nft_balance(
@@ -372,7 +414,7 @@ fn compound_rewards_guild_master_test() {
             0,
             &rust_biguint!(farm_in_amount),
             |sc| {
-                let _ = sc.stake_farm_endpoint(OptionalValue::None);
+                let _ = sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
             },
         )
         .assert_ok();
@@ -383,8 +425,8 @@ fn compound_rewards_guild_master_test() {
     farm_setup.set_block_nonce(10);
 
     // value taken from the "test_unstake_farm" test
-    let expected_reward_token_out = 39;
-    let expected_reward_per_share = 399_999;
+    let expected_reward_token_out = 46;
+    let expected_reward_per_share = 469_999;
 
     farm_setup
         .b_mock
@@ -404,6 +446,7 @@ fn compound_rewards_guild_master_test() {
         reward_per_share: managed_biguint!(expected_reward_per_share),
         compounded_reward: managed_biguint!(expected_reward_token_out),
         current_farm_amount: managed_biguint!(farm_in_amount + expected_reward_token_out),
+        lock: None,
     };
 
     farm_setup.b_mock.check_nft_balance(
@@ -436,13 +479,10 @@ fn claim_rewards_multi_test() {
     farm_setup.set_block_nonce(10);
 
     // value taken from the "test_unstake_farm" test
-    let expected_reward_token_out = 40;
+    let expected_reward_token_out = 47;
 
-    // Not sure why the +10 difference here. Likely rounding errors.
     let expected_farming_token_balance = rust_biguint!(
-        USER_TOTAL_RIDE_TOKENS - farm_in_amount - farm_in_amount
-            + expected_reward_token_out * 2
-            + 10
+        USER_TOTAL_RIDE_TOKENS - farm_in_amount - farm_in_amount + expected_reward_token_out * 2
     );
 
     let all_farm_tokens = [
@@ -464,7 +504,7 @@ fn claim_rewards_multi_test() {
             &farm_setup.first_farm_wrapper,
             &all_farm_tokens,
             |sc| {
-                let _ = sc.claim_rewards();
+                let _ = sc.claim_rewards(OptionalValue::None);
             },
         )
         .assert_ok();
@@ -514,7 +554,7 @@ where
 
     let total_amount = farm_in_amount + second_farm_in_amount + 1;
     let first_reward_share = 0;
-    let second_reward_share = 400_000;
+    let second_reward_share = 470_000;
     let expected_reward_per_share = (first_reward_share * farm_in_amount
         + second_reward_share * second_farm_in_amount
         + total_amount
@@ -557,7 +597,7 @@ fn test_exit_farm_after_enter_twice() {
     farm_setup.set_block_epoch(8);
     farm_setup.set_block_nonce(25);
 
-    let expected_rewards = 83;
+    let expected_rewards = 86;
     let expected_ride_token_balance =
         rust_biguint!(USER_TOTAL_RIDE_TOKENS) - farm_in_amount - second_farm_in_amount
             + expected_rewards;
@@ -592,14 +632,14 @@ fn test_unbond() {
     farm_setup.set_block_epoch(current_epoch);
     farm_setup.set_block_nonce(current_block);
 
-    let block_diff = current_block;
-    let expected_rewards_unbounded = block_diff * PER_BLOCK_REWARD_AMOUNT;
+    let elapsed_seconds = current_block * SECONDS_PER_BLOCK;
+    let expected_rewards_unbounded = elapsed_seconds * PER_SECOND_REWARD_AMOUNT;
 
-    // ~= 4 * 10 = 40
+    // ~= 0.79 * 60 = 47
     let expected_rewards_max_apr =
-        farm_in_amount * MAX_APR / MAX_PERCENT / BLOCKS_IN_YEAR * block_diff;
+        farm_in_amount * MAX_APR * elapsed_seconds / MAX_PERCENT / SECONDS_IN_YEAR;
     let expected_rewards = core::cmp::min(expected_rewards_unbounded, expected_rewards_max_apr);
-    assert_eq!(expected_rewards, 40);
+    assert_eq!(expected_rewards, 47);
 
     let expected_ride_token_balance =
         rust_biguint!(USER_TOTAL_RIDE_TOKENS) - farm_in_amount + expected_rewards;
@@ -643,14 +683,14 @@ fn cancel_unbond_test() {
     farm_setup.set_block_epoch(current_epoch);
     farm_setup.set_block_nonce(current_block);
 
-    let block_diff = current_block;
-    let expected_rewards_unbounded = block_diff * PER_BLOCK_REWARD_AMOUNT;
+    let elapsed_seconds = current_block * SECONDS_PER_BLOCK;
+    let expected_rewards_unbounded = elapsed_seconds * PER_SECOND_REWARD_AMOUNT;
 
-    // ~= 4 * 10 = 40
+    // ~= 0.79 * 60 = 47
     let expected_rewards_max_apr =
-        farm_in_amount * MAX_APR / MAX_PERCENT / BLOCKS_IN_YEAR * block_diff;
+        farm_in_amount * MAX_APR * elapsed_seconds / MAX_PERCENT / SECONDS_IN_YEAR;
     let expected_rewards = core::cmp::min(expected_rewards_unbounded, expected_rewards_max_apr);
-    assert_eq!(expected_rewards, 40);
+    assert_eq!(expected_rewards, 47);
 
     let expected_ride_token_balance =
         rust_biguint!(USER_TOTAL_RIDE_TOKENS) - farm_in_amount + expected_rewards;
@@ -788,7 +828,7 @@ fn calculate_rewards_test() {
     farm_setup.set_block_nonce(10);
 
     // value taken from the "test_unstake_farm" test
-    let expected_reward_token_out = 40;
+    let expected_reward_token_out = 47;
 
     let user_addr = farm_setup.user_address.clone();
     farm_setup
@@ -798,6 +838,7 @@ fn calculate_rewards_test() {
                 reward_per_share: managed_biguint!(0),
                 compounded_reward: managed_biguint!(0),
                 current_farm_amount: managed_biguint!(farm_in_amount),
+                lock: None,
             };
 
             let calculated_reward = sc.calculate_rewards_for_given_position(
@@ -811,7 +852,7 @@ fn calculate_rewards_test() {
 
     let expected_farming_token_balance =
         rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + expected_reward_token_out);
-    let expected_reward_per_share = 400_000;
+    let expected_reward_per_share = 470_000;
     farm_setup.claim_rewards(
         farm_in_amount,
         expected_farm_token_nonce,
@@ -833,6 +874,7 @@ fn calculate_rewards_test() {
                 reward_per_share: managed_biguint!(expected_reward_per_share),
                 compounded_reward: managed_biguint!(0),
                 current_farm_amount: managed_biguint!(farm_in_amount),
+                lock: None,
             };
 
             let _ = sc.calculate_rewards_for_given_position(
@@ -852,6 +894,7 @@ fn calculate_rewards_test() {
                 reward_per_share: managed_biguint!(0),
                 compounded_reward: managed_biguint!(0),
                 current_farm_amount: managed_biguint!(1),
+                lock: None,
             };
 
             let _ = sc.calculate_rewards_for_given_position(
@@ -882,8 +925,8 @@ fn try_manipulate_staked_amounts_test() {
     farm_setup.set_block_nonce(10);
 
     // value taken from the "test_unstake_farm" test
-    let expected_reward_token_out = 40;
-    let expected_reward_per_share = 400_000;
+    let expected_reward_token_out = 47;
+    let expected_reward_per_share = 470_000;
 
     farm_setup
         .b_mock
@@ -903,6 +946,7 @@ fn try_manipulate_staked_amounts_test() {
         reward_per_share: managed_biguint!(expected_reward_per_share),
         compounded_reward: managed_biguint!(expected_reward_token_out),
         current_farm_amount: managed_biguint!(farm_in_amount + expected_reward_token_out),
+        lock: None,
     };
 
     farm_setup.b_mock.check_nft_balance(
@@ -973,8 +1017,8 @@ fn token_splitting_test() {
     farm_setup.set_block_nonce(10);
 
     // value taken from the "test_unstake_farm" test
-    let expected_reward_token_out = 40;
-    let expected_reward_per_share = 400_000;
+    let expected_reward_token_out = 47;
+    let expected_reward_per_share = 470_000;
 
     farm_setup
         .b_mock
@@ -994,6 +1038,7 @@ fn token_splitting_test() {
         reward_per_share: managed_biguint!(expected_reward_per_share),
         compounded_reward: managed_biguint!(expected_reward_token_out),
         current_farm_amount: managed_biguint!(farm_in_amount + expected_reward_token_out),
+        lock: None,
     };
 
     farm_setup.b_mock.check_nft_balance(
@@ -1040,3 +1085,3419 @@ fn token_splitting_test() {
         )
         .assert_ok();
 }
+
+#[test]
+fn boosted_yields_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let energy_factory_wrapper = farm_setup.b_mock.create_sc_account(
+        &rust_biguint!(0),
+        Some(&farm_setup.first_owner_address),
+        energy_factory_mock::contract_obj,
+        "energy factory",
+    );
+
+    let user_addr = farm_setup.user_address.clone();
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &energy_factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_user_energy(managed_address!(&user_addr), managed_biguint!(1_000));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_boosted_yields_factors(
+                    managed_biguint!(10),
+                    managed_biguint!(3),
+                    managed_biguint!(2),
+                    managed_biguint!(1),
+                    managed_biguint!(1),
+                );
+                sc.set_energy_factory_address(managed_address!(
+                    energy_factory_wrapper.address_ref()
+                ));
+            },
+        )
+        .assert_ok();
+
+    // only guild master may set the percentage
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_boosted_yields_rewards_percentage(5_000);
+            },
+        )
+        .assert_user_error("Only guild master may set boosted yields percentage");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_boosted_yields_rewards_percentage(5_000);
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 2;
+    farm_setup.stake_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0);
+
+    // week 2 - half of the user rewards go into the boosted yields pool
+    farm_setup.set_block_epoch(7);
+    farm_setup.set_block_nonce(10);
+
+    let expected_reward_token_out = 24;
+    let mut expected_farming_token_balance =
+        rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + expected_reward_token_out);
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce,
+        expected_reward_token_out,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 1,
+        240_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(sc.get_current_week(), 2);
+            assert_eq!(sc.boosted_rewards_for_week(2).get(), managed_biguint!(23));
+            assert_eq!(
+                sc.total_farm_amount_for_week(2).get(),
+                managed_biguint!(farm_in_amount)
+            );
+            assert_eq!(sc.total_energy_for_week(2).get(), managed_biguint!(1_000));
+        })
+        .assert_ok();
+
+    // week 3 - user receives the whole week 2 pool as boosted rewards
+    farm_setup.set_block_epoch(14);
+    farm_setup.set_block_nonce(20);
+
+    let expected_reward_token_out = 24 + 23;
+    expected_farming_token_balance += rust_biguint!(expected_reward_token_out);
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce + 1,
+        expected_reward_token_out,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 2,
+        480_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(
+                sc.user_unclaimed_boosted_rewards(&managed_address!(&user_addr))
+                    .get(),
+                managed_biguint!(0)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn transfer_position_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 2;
+    farm_setup.stake_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0);
+
+    let receiver_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+
+    // guild master may not transfer their position
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            |sc| {
+                sc.transfer_position(managed_address!(&receiver_addr));
+            },
+        )
+        .assert_user_error("Guild master positions may not be transferred");
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            expected_farm_token_nonce,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                sc.transfer_position(managed_address!(&receiver_addr));
+            },
+        )
+        .assert_ok();
+
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &receiver_addr,
+        FARM_TOKEN_ID,
+        expected_farm_token_nonce,
+        &rust_biguint!(farm_in_amount),
+        None,
+    );
+
+    let user_addr = farm_setup.user_address.clone();
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(
+                sc.get_user_staked_tokens(managed_address!(&user_addr)),
+                managed_biguint!(0)
+            );
+            assert_eq!(
+                sc.get_user_staked_tokens(managed_address!(&receiver_addr)),
+                managed_biguint!(farm_in_amount)
+            );
+        })
+        .assert_ok();
+
+    // receiver gets the rewards accumulated by the position
+    farm_setup.set_block_epoch(5);
+    farm_setup.set_block_nonce(10);
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &receiver_addr,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            expected_farm_token_nonce,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let (_, rewards_payment) = sc.unstake_farm().into_tuple();
+                assert_eq!(rewards_payment.amount, managed_biguint!(47));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&receiver_addr, REWARD_TOKEN_ID, &rust_biguint!(47));
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(
+                sc.get_user_staked_tokens(managed_address!(&receiver_addr)),
+                managed_biguint!(0)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn guild_master_handover_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_max_guild_master_commission(1_000);
+                sc.set_commission_notice_seconds(0);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_guild_master_commission_endpoint(1_000);
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+    farm_setup.set_block_nonce(10);
+
+    let new_master_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            |sc| {
+                sc.propose_guild_master(managed_address!(&new_master_addr));
+            },
+        )
+        .assert_ok();
+
+    // only the proposed address may accept
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.accept_guild_master();
+            },
+        )
+        .assert_user_error("Only the proposed guild master may accept");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &new_master_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.accept_guild_master();
+            },
+        )
+        .assert_ok();
+
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &new_master_addr,
+        FARM_TOKEN_ID,
+        1,
+        &rust_biguint!(1),
+        None,
+    );
+
+    // the old guild master is paid 10% of the 47 user rewards accrued before the handover
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.first_owner_address,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(4),
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &new_master_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let commission = sc.claim_guild_master_commission();
+                assert_eq!(commission.amount, managed_biguint!(0));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(
+                sc.guild_master_address().get(),
+                managed_address!(&new_master_addr)
+            );
+            assert!(sc.pending_guild_master().is_empty());
+            assert_eq!(
+                sc.get_user_staked_tokens(managed_address!(&new_master_addr)),
+                managed_biguint!(1)
+            );
+        })
+        .assert_ok();
+
+    let first_guild_addr = farm_setup.first_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            let first_guild = sc
+                .get_all_guilds()
+                .into_iter()
+                .find(|guild| guild.guild == managed_address!(&first_guild_addr))
+                .unwrap();
+            assert_eq!(first_guild.guild_master, managed_address!(&new_master_addr));
+        })
+        .assert_ok();
+
+    // old guild master lost their rights
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_boosted_yields_rewards_percentage(5_000);
+            },
+        )
+        .assert_user_error("Only guild master may set boosted yields percentage");
+}
+
+#[test]
+fn upgrade_guilds_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let source_address = sc.guild_sc_source_address().get();
+                sc.set_guild_source_address(source_address);
+
+                assert_eq!(sc.current_guild_code_version().get(), 1);
+                assert_eq!(sc.guilds_pending_upgrade().get(), 2);
+            },
+        )
+        .assert_ok();
+
+    // upgrade in batches of one guild
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let pending = sc.upgrade_guilds(1);
+                assert_eq!(pending, 1);
+                assert_eq!(sc.upgrade_cursor().get(), 2);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let pending = sc.upgrade_guilds(5);
+                assert_eq!(pending, 0);
+
+                for guild_id in sc.deployed_guilds().iter() {
+                    assert_eq!(sc.guild_code_version(guild_id).get(), 1);
+                }
+            },
+        )
+        .assert_ok();
+
+    // guilds keep working after the upgrade
+    farm_setup.stake_farm(100_000_000, &[], 2, 0, 0);
+}
+
+#[test]
+fn pending_rewards_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 2;
+    farm_setup.stake_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0);
+
+    farm_setup.set_block_epoch(5);
+    farm_setup.set_block_nonce(10);
+
+    let user_addr = farm_setup.user_address.clone();
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            let mut positions = MultiValueEncoded::new();
+            positions.push((expected_farm_token_nonce, managed_biguint!(farm_in_amount)).into());
+
+            let pending_rewards = sc.get_pending_rewards(managed_address!(&user_addr), positions);
+            assert!(!pending_rewards.is_guild_master);
+            assert_eq!(
+                pending_rewards.base_rewards,
+                managed_biguint!(PER_SECOND_REWARD_AMOUNT * 10 * SECONDS_PER_BLOCK)
+            );
+            assert_eq!(pending_rewards.apr_bounded_rewards, managed_biguint!(47));
+            assert_eq!(pending_rewards.reward_per_share, managed_biguint!(470_000));
+            assert_eq!(pending_rewards.claimable_rewards, managed_biguint!(47));
+            assert_eq!(pending_rewards.boosted_rewards, managed_biguint!(0));
+
+            // nothing was written to storage
+            assert_eq!(sc.last_reward_timestamp().get(), 0);
+            assert_eq!(sc.user_rps().get(), managed_biguint!(0));
+        })
+        .assert_ok();
+
+    // the simulated rewards match the claimed rewards
+    let expected_farming_token_balance =
+        rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + 47);
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce,
+        47,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 1,
+        470_000,
+    );
+}
+
+#[test]
+fn reward_curves_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut user_tiers = MultiValueEncoded::new();
+                user_tiers.push((5_000, 1_000).into());
+                user_tiers.push((MAX_PERCENT, 2_000).into());
+                sc.update_user_tiers(user_tiers);
+
+                sc.set_user_reward_curve(RewardCurve::PiecewiseLinear);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.update_all();
+
+                assert_eq!(sc.find_user_tier_apr(2_500), 1_000);
+                assert_eq!(sc.find_user_tier_apr(5_000), 1_000);
+                assert_eq!(sc.find_user_tier_apr(7_500), 1_500);
+                assert_eq!(sc.find_user_tier_apr(MAX_PERCENT), 2_000);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_user_reward_curve(RewardCurve::InverseStakedRatio(
+                    InverseStakedRatioParams {
+                        numerator: 5_000_000,
+                        min_apr: 500,
+                        max_apr: 2_000,
+                    },
+                ));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.update_all();
+
+                assert_eq!(sc.find_user_tier_apr(1_000), 2_000);
+                assert_eq!(sc.find_user_tier_apr(4_000), 1_250);
+                assert_eq!(sc.find_user_tier_apr(5_000), 1_000);
+                assert_eq!(sc.find_user_tier_apr(MAX_PERCENT), 500);
+            },
+        )
+        .assert_ok();
+
+    // invalid params are rejected
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_user_reward_curve(RewardCurve::InverseStakedRatio(
+                    InverseStakedRatioParams {
+                        numerator: 5_000_000,
+                        min_apr: 2_000,
+                        max_apr: 500,
+                    },
+                ));
+            },
+        )
+        .assert_user_error("Invalid curve params");
+}
+
+#[test]
+fn scheduled_tiers_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup.set_block_nonce(5);
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut user_tiers = MultiValueEncoded::new();
+                user_tiers.push((MAX_PERCENT, 1_000).into());
+                sc.schedule_user_tiers_update(60, user_tiers);
+
+                assert_eq!(sc.scheduled_user_tiers_timestamp().get(), 60);
+                assert_eq!(sc.scheduled_user_tiers().len(), 1);
+            },
+        )
+        .assert_ok();
+
+    // only one pending change per track
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut user_tiers = MultiValueEncoded::new();
+                user_tiers.push((MAX_PERCENT, 1_500).into());
+                sc.schedule_user_tiers_update(120, user_tiers);
+            },
+        )
+        .assert_user_error("Tiers change already scheduled");
+
+    // the guild keeps the old tiers until the scheduled timestamp
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.update_all();
+
+                assert_eq!(sc.find_user_tier_apr(MAX_PERCENT), MAX_APR);
+
+                let next_switch_timestamp = |after, until| {
+                    sc.get_next_config_switch(after, until)
+                        .map(|(timestamp, _)| timestamp)
+                };
+                assert_eq!(next_switch_timestamp(30, 120), Some(60));
+                assert_eq!(next_switch_timestamp(60, 120), None);
+                assert_eq!(next_switch_timestamp(30, 59), None);
+            },
+        )
+        .assert_ok();
+
+    farm_setup.set_block_nonce(10);
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let (_, switch) = sc.get_next_config_switch(30, 60).unwrap();
+                sc.apply_config_switch(switch);
+                assert_eq!(sc.find_user_tier_apr(MAX_PERCENT), 1_000);
+            },
+        )
+        .assert_ok();
+
+    // cancellation is only possible before activation
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.cancel_scheduled_user_tiers_update();
+            },
+        )
+        .assert_user_error("Timestamp must be in the future");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.apply_scheduled_tiers();
+
+                assert!(sc.scheduled_user_tiers_timestamp().is_empty());
+                assert_eq!(sc.user_tiers().get(1).apr, 1_000);
+
+                let checkpoints = sc.config_checkpoints();
+                assert_eq!(checkpoints.get(checkpoints.len()).timestamp, 60);
+
+                let mut user_tiers = MultiValueEncoded::new();
+                user_tiers.push((MAX_PERCENT, 1_500).into());
+                sc.schedule_user_tiers_update(120, user_tiers);
+                sc.cancel_scheduled_user_tiers_update();
+
+                assert!(sc.scheduled_user_tiers_timestamp().is_empty());
+                assert!(sc.scheduled_user_tiers().is_empty());
+            },
+        )
+        .assert_ok();
+
+    // guilds touched after the config applied the change still switch at the scheduled timestamp
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.second_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let (switch_timestamp, _) = sc.get_next_config_switch(30, 120).unwrap();
+                assert_eq!(switch_timestamp, 60);
+
+                sc.update_all();
+                assert_eq!(sc.find_user_tier_apr(MAX_PERCENT), 1_000);
+            },
+        )
+        .assert_ok();
+
+    // setters apply the due scheduled tiers first, so checkpoints stay ordered
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut user_tiers = MultiValueEncoded::new();
+                user_tiers.push((MAX_PERCENT, 1_500).into());
+                sc.schedule_user_tiers_update(90, user_tiers);
+            },
+        )
+        .assert_ok();
+
+    farm_setup.set_block_nonce(20);
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_per_second_reward_amount(managed_biguint!(PER_SECOND_REWARD_AMOUNT));
+
+                assert!(sc.scheduled_user_tiers_timestamp().is_empty());
+                assert_eq!(sc.user_tiers().get(1).apr, 1_500);
+
+                let checkpoints = sc.config_checkpoints();
+                let checkpoints_len = checkpoints.len();
+                assert_eq!(checkpoints.get(checkpoints_len - 1).timestamp, 90);
+                assert_eq!(checkpoints.get(checkpoints_len).timestamp, 120);
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn checkpointed_accrual_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 2;
+    farm_setup.stake_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0);
+
+    // halve the user APR while the guild is idle
+    farm_setup.set_block_nonce(5);
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut user_tiers = MultiValueEncoded::new();
+                user_tiers.push((MAX_PERCENT, MAX_APR / 2).into());
+                sc.update_user_tiers(user_tiers);
+
+                let checkpoints = sc.config_checkpoints();
+                assert_eq!(checkpoints.get(checkpoints.len()).timestamp, 30);
+            },
+        )
+        .assert_ok();
+
+    // 30 seconds at the old APR (23 rewards), then 30 seconds at the new APR (11 rewards)
+    farm_setup.set_block_epoch(5);
+    farm_setup.set_block_nonce(10);
+
+    let expected_rewards = 23 + 11;
+    let expected_farming_token_balance =
+        rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + expected_rewards);
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce,
+        expected_rewards,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 1,
+        340_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            let checkpoints_len = sc.get_config_checkpoints_mapper().len();
+            assert_eq!(sc.last_config_checkpoint().get(), checkpoints_len);
+            assert_eq!(sc.find_user_tier_apr(MAX_PERCENT), MAX_APR / 2);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn config_switches_cap_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 2;
+    farm_setup.stake_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0);
+
+    let mut initial_checkpoints_len = 0;
+    farm_setup.set_block_nonce(5);
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                initial_checkpoints_len = sc.config_checkpoints().len();
+                for _ in 0..MAX_CONFIG_SWITCHES_PER_CALL + 5 {
+                    sc.set_per_second_reward_amount(managed_biguint!(PER_SECOND_REWARD_AMOUNT));
+                }
+            },
+        )
+        .assert_ok();
+
+    // the first claim stops accruing at the last processed switch
+    farm_setup.set_block_nonce(10);
+    let mut expected_rewards = 23;
+    let mut expected_farming_token_balance =
+        rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + expected_rewards);
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce,
+        expected_rewards,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 1,
+        230_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(sc.last_reward_timestamp().get(), 5 * SECONDS_PER_BLOCK);
+            assert_eq!(
+                sc.last_config_checkpoint().get(),
+                initial_checkpoints_len + MAX_CONFIG_SWITCHES_PER_CALL
+            );
+        })
+        .assert_ok();
+
+    // the next claim processes the remaining switches
+    expected_rewards = 23;
+    expected_farming_token_balance += rust_biguint!(expected_rewards);
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce + 1,
+        expected_rewards,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 2,
+        460_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(sc.last_reward_timestamp().get(), 10 * SECONDS_PER_BLOCK);
+            assert_eq!(
+                sc.last_config_checkpoint().get(),
+                sc.get_config_checkpoints_mapper().len()
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn compound_vault_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let referrer_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_vault_compound_bounty(500);
+                sc.set_referral_program(1_000, 10);
+                sc.set_referrer(
+                    &managed_address!(&farm_setup.user_address),
+                    managed_address!(&referrer_addr),
+                );
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 2;
+    farm_setup.stake_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0);
+
+    // guild master may not use the vault
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            |sc| {
+                let _ = sc.deposit_to_vault();
+            },
+        )
+        .assert_user_error("Guild master may not use the vault");
+
+    // the pending 47 rewards are compounded on deposit
+    farm_setup.set_block_epoch(5);
+    farm_setup.set_block_nonce(10);
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            expected_farm_token_nonce,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let shares = sc.deposit_to_vault();
+                assert_eq!(shares, managed_biguint!(farm_in_amount + 47));
+
+                // the referrer earns on the compounded rewards as well
+                assert_eq!(
+                    sc.referral_rewards(&managed_address!(&referrer_addr)).get(),
+                    managed_biguint!(4)
+                );
+            },
+        )
+        .assert_ok();
+
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        FARM_TOKEN_ID,
+        expected_farm_token_nonce,
+        &rust_biguint!(0),
+        None,
+    );
+
+    let user_addr = farm_setup.user_address.clone();
+    let guild_addr = farm_setup.first_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(
+                sc.get_user_staked_tokens(managed_address!(&user_addr)),
+                managed_biguint!(0)
+            );
+            assert_eq!(
+                sc.get_user_staked_tokens(managed_address!(&guild_addr)),
+                managed_biguint!(farm_in_amount + 47)
+            );
+            assert_eq!(
+                sc.get_vault_balance(managed_address!(&user_addr)),
+                managed_biguint!(farm_in_amount + 47)
+            );
+        })
+        .assert_ok();
+
+    // anyone may compound, receiving 5% of the vault rewards
+    farm_setup.set_block_nonce(20);
+
+    let keeper_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &keeper_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let bounty = sc.compound_vault_endpoint();
+                assert_eq!(bounty.amount, managed_biguint!(2));
+                assert_eq!(
+                    sc.vault_farm_amount().get(),
+                    managed_biguint!(farm_in_amount + 47 + 44)
+                );
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&keeper_addr, REWARD_TOKEN_ID, &rust_biguint!(2));
+
+    // withdrawing returns a fresh farm token with the compounded amount
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(sc.vault_compounded_rewards().get(), managed_biguint!(91));
+        })
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let new_farm_token = sc.withdraw_from_vault(managed_biguint!(farm_in_amount + 47));
+                assert_eq!(new_farm_token.token_nonce, expected_farm_token_nonce + 1);
+                assert_eq!(new_farm_token.amount, managed_biguint!(farm_in_amount + 91));
+
+                assert_eq!(sc.total_vault_shares().get(), managed_biguint!(0));
+                assert_eq!(sc.vault_farm_amount().get(), managed_biguint!(0));
+                assert_eq!(sc.vault_compounded_rewards().get(), managed_biguint!(0));
+            },
+        )
+        .assert_ok();
+
+    // only the deposited base is removed from the base staked tokens on unstake
+    let expected_attributes = StakingFarmTokenAttributes::<DebugApi> {
+        reward_per_share: managed_biguint!(939_999),
+        compounded_reward: managed_biguint!(91),
+        current_farm_amount: managed_biguint!(farm_in_amount + 91),
+        lock: None,
+    };
+    farm_setup.b_mock.check_nft_balance(
+        &farm_setup.user_address,
+        FARM_TOKEN_ID,
+        expected_farm_token_nonce + 1,
+        &rust_biguint!(farm_in_amount + 91),
+        Some(&expected_attributes),
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.vault_shares(&managed_address!(&user_addr))
+                    .set(managed_biguint!(1));
+                sc.total_vault_shares().set(managed_biguint!(1));
+
+                let _ = sc.withdraw_from_vault(managed_biguint!(1));
+            },
+        )
+        .assert_user_error("Withdraw too small");
+}
+
+#[test]
+fn merge_and_partial_unbond_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+    farm_setup.stake_farm(farm_in_amount, &[], 3, 0, 0);
+    farm_setup.stake_farm(farm_in_amount, &[], 4, 0, 0);
+
+    farm_setup.set_block_nonce(10);
+
+    // unbond tokens unlocking at epochs 10, 12 and 13
+    for (epoch, farm_token_nonce) in [(5, 2), (7, 3), (8, 4)] {
+        farm_setup.set_block_epoch(epoch);
+        farm_setup
+            .b_mock
+            .execute_esdt_transfer(
+                &farm_setup.user_address,
+                &farm_setup.first_farm_wrapper,
+                FARM_TOKEN_ID,
+                farm_token_nonce,
+                &rust_biguint!(farm_in_amount),
+                |sc| {
+                    let _ = sc.unstake_farm();
+                },
+            )
+            .assert_ok();
+    }
+
+    // the matured token is released, the immature one is returned
+    farm_setup.set_block_epoch(10);
+
+    let unbond_payments = [
+        TxTokenTransfer {
+            token_identifier: UNBOND_TOKEN_ID.to_vec(),
+            nonce: 1,
+            value: rust_biguint!(farm_in_amount),
+        },
+        TxTokenTransfer {
+            token_identifier: UNBOND_TOKEN_ID.to_vec(),
+            nonce: 2,
+            value: rust_biguint!(farm_in_amount),
+        },
+    ];
+    farm_setup
+        .b_mock
+        .execute_esdt_multi_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &unbond_payments,
+            |sc| {
+                let farming_tokens = sc.unbond_farm();
+                assert_eq!(farming_tokens.amount, managed_biguint!(farm_in_amount));
+            },
+        )
+        .assert_ok();
+
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        UNBOND_TOKEN_ID,
+        1,
+        &rust_biguint!(0),
+        None,
+    );
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        UNBOND_TOKEN_ID,
+        2,
+        &rust_biguint!(farm_in_amount),
+        None,
+    );
+
+    // all immature tokens still fail
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            UNBOND_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.unbond_farm();
+            },
+        )
+        .assert_user_error("Unbond period not over");
+
+    let unbond_payments = [
+        TxTokenTransfer {
+            token_identifier: UNBOND_TOKEN_ID.to_vec(),
+            nonce: 2,
+            value: rust_biguint!(farm_in_amount),
+        },
+        TxTokenTransfer {
+            token_identifier: UNBOND_TOKEN_ID.to_vec(),
+            nonce: 3,
+            value: rust_biguint!(farm_in_amount),
+        },
+    ];
+    farm_setup
+        .b_mock
+        .execute_esdt_multi_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &unbond_payments,
+            |sc| {
+                let merged_token = sc.merge_unbond_tokens();
+                assert_eq!(merged_token.token_nonce, 4);
+                assert_eq!(merged_token.amount, managed_biguint!(2 * farm_in_amount));
+
+                let attributes: UnbondSftAttributes<DebugApi> =
+                    sc.unbond_token().get_token_attributes(4);
+                assert_eq!(attributes.unlock_epoch, 13);
+                assert_eq!(
+                    attributes
+                        .opt_original_attributes
+                        .unwrap()
+                        .current_farm_amount,
+                    managed_biguint!(2 * farm_in_amount)
+                );
+            },
+        )
+        .assert_ok();
+
+    // the merged token may still cancel unbond
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            UNBOND_TOKEN_ID,
+            4,
+            &rust_biguint!(2 * farm_in_amount),
+            |sc| {
+                let farm_token = sc.cancel_unbond();
+                assert_eq!(farm_token.amount, managed_biguint!(2 * farm_in_amount));
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn separate_reward_token_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let bonus_token_id = b"BONUS-abcdef";
+    let new_guild_master = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &new_guild_master,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.deploy_guild(OptionalValue::Some(managed_token_id!(bonus_token_id)));
+            },
+        )
+        .assert_user_error("Invalid reward token");
+
+    farm_setup.b_mock.set_esdt_balance(
+        &farm_setup.first_owner_address,
+        bonus_token_id,
+        &rust_biguint!(1_000_000),
+    );
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut reward_tokens = MultiValueEncoded::new();
+                reward_tokens.push(managed_token_id!(bonus_token_id));
+                sc.add_reward_tokens(reward_tokens);
+            },
+        )
+        .assert_ok();
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            bonus_token_id,
+            0,
+            &rust_biguint!(1_000_000),
+            |sc| {
+                sc.deposit_rewards_admins();
+            },
+        )
+        .assert_ok();
+
+    // simulate a guild deployed with the bonus token as reward token
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.reward_token_id().set(managed_token_id!(bonus_token_id));
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+
+    farm_setup.set_block_nonce(10);
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.compound_rewards();
+            },
+        )
+        .assert_user_error("Compounding not available");
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.claim_rewards(OptionalValue::None);
+            },
+        )
+        .assert_ok();
+
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.user_address,
+        bonus_token_id,
+        &rust_biguint!(47),
+    );
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.user_address,
+        FARMING_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount),
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            assert_eq!(
+                sc.remaining_rewards(&managed_token_id!(bonus_token_id))
+                    .get(),
+                managed_biguint!(1_000_000 - 470)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn guild_master_commission_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_max_guild_master_commission(1_000);
+                sc.set_commission_notice_seconds(5 * SECONDS_PER_BLOCK);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_guild_master_commission_endpoint(1_001);
+            },
+        )
+        .assert_user_error("Commission too high");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_guild_master_commission_endpoint(1_000);
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+
+    // increase not applied before the notice period ends
+    farm_setup.set_block_nonce(4);
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(sc.get_guild_master_commission(), 0);
+        })
+        .assert_ok();
+
+    let mut expected_balance = USER_TOTAL_RIDE_TOKENS - farm_in_amount + 19;
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        2,
+        19,
+        &rust_biguint!(expected_balance),
+        &rust_biguint!(expected_balance),
+        3,
+        190_000,
+    );
+
+    // the guild is idle across the end of the notice period: of the 4 + 23 rewards,
+    // only the 23 accrued after it pay 10% to the guild master
+    farm_setup.set_block_nonce(10);
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(sc.get_guild_master_commission(), 1_000);
+
+            let pending_rewards = sc.get_pending_rewards(
+                managed_address!(&farm_setup.user_address),
+                MultiValueEncoded::new(),
+            );
+            assert_eq!(pending_rewards.guild_master_commission, 1_000);
+            assert_eq!(pending_rewards.reward_per_share, managed_biguint!(440_000));
+        })
+        .assert_ok();
+
+    expected_balance += 25;
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        3,
+        25,
+        &rust_biguint!(expected_balance),
+        &rust_biguint!(expected_balance),
+        4,
+        440_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.claim_guild_master_commission();
+            },
+        )
+        .assert_user_error("Only guild master may claim commission");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let commission = sc.claim_guild_master_commission();
+                assert_eq!(commission.amount, managed_biguint!(2));
+                assert_eq!(sc.accumulated_guild_master_commission().get(), 0);
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn instant_unbond_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+    farm_setup.stake_farm(farm_in_amount, &[], 3, 0, 0);
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.unstake_farm_instant();
+            },
+        )
+        .assert_user_error("Instant unbond not available");
+
+    // 10% fee for the full unbond period
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_instant_unbond_fee(1_000);
+            },
+        )
+        .assert_ok();
+
+    farm_setup.set_block_epoch(5);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let reward_capacity = sc.reward_capacity().get();
+
+                let (farming_tokens, _) = sc.unstake_farm_instant().into_tuple();
+                assert_eq!(farming_tokens.amount, managed_biguint!(90_000_000));
+                assert_eq!(
+                    sc.reward_capacity().get(),
+                    reward_capacity + managed_biguint!(10_000_000)
+                );
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            3,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.unstake_farm();
+            },
+        )
+        .assert_ok();
+
+    // 2 of the 5 unbond epochs remaining
+    farm_setup.set_block_epoch(8);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            UNBOND_TOKEN_ID,
+            1,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let reward_capacity = sc.reward_capacity().get();
+
+                let farming_tokens = sc.instant_unbond();
+                assert_eq!(farming_tokens.amount, managed_biguint!(96_000_000));
+                assert_eq!(
+                    sc.reward_capacity().get(),
+                    reward_capacity + managed_biguint!(4_000_000)
+                );
+            },
+        )
+        .assert_ok();
+
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.user_address,
+        FARMING_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_RIDE_TOKENS - 2 * farm_in_amount + 90_000_000 + 96_000_000),
+    );
+}
+
+#[test]
+fn locked_position_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut options = MultiValueEncoded::new();
+                options.push((30, 15_000).into());
+                options.push((90, 20_000).into());
+                sc.add_lock_options(options);
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+    farm_setup.stake_farm(farm_in_amount, &[], 3, 0, 0);
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.lock_position(45);
+            },
+        )
+        .assert_user_error("Invalid lock option");
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let (new_farm_token, _) = sc.lock_position(90).into_tuple();
+                assert_eq!(new_farm_token.token_nonce, 4);
+                assert_eq!(
+                    sc.user_lock_extra_weight().get(),
+                    managed_biguint!(farm_in_amount)
+                );
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            4,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.lock_position(30);
+            },
+        )
+        .assert_user_error("Lock may not be shortened");
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            4,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.unstake_farm();
+            },
+        )
+        .assert_user_error("Position locked");
+
+    // positions deposited for delegates are claimed together, so their locks must match
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            3,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                sc.deposit_delegated_positions();
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            4,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                sc.deposit_delegated_positions();
+            },
+        )
+        .assert_user_error("Positions with different locks");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.withdraw_delegated_positions();
+            },
+        )
+        .assert_ok();
+
+    // the locked position counts double
+    farm_setup.set_block_nonce(10);
+    let mut unlocked_rewards = 0;
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            3,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let (_, rewards) = sc.claim_rewards(OptionalValue::None).into_tuple();
+                unlocked_rewards = rewards.amount.to_u64().unwrap();
+            },
+        )
+        .assert_ok();
+    assert!(unlocked_rewards > 0);
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            4,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let (new_farm_token, rewards) = sc.claim_rewards(OptionalValue::None).into_tuple();
+                assert_eq!(new_farm_token.token_nonce, 6);
+                assert_eq!(rewards.amount, managed_biguint!(2 * unlocked_rewards));
+            },
+        )
+        .assert_ok();
+
+    // the lock expired
+    farm_setup.set_block_epoch(90);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            6,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.unstake_farm();
+                assert_eq!(sc.user_lock_extra_weight().get(), managed_biguint!(0));
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn lock_expiry_without_interaction_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut options = MultiValueEncoded::new();
+                options.push((90, 20_000).into());
+                sc.add_lock_options(options);
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+    farm_setup.stake_farm(farm_in_amount, &[], 3, 0, 0);
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let (new_farm_token, _) = sc.lock_position(90).into_tuple();
+                assert_eq!(new_farm_token.token_nonce, 4);
+            },
+        )
+        .assert_ok();
+
+    // no one interacts until after the lock expired,
+    // so the locked position keeps its extra weight for all the accrued rewards
+    farm_setup.set_block_epoch(100);
+    farm_setup.set_block_nonce(10);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            4,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let (_, rewards) = sc.claim_rewards(OptionalValue::None).into_tuple();
+                assert_eq!(rewards.amount, managed_biguint!(62));
+                assert_eq!(sc.user_lock_extra_weight().get(), managed_biguint!(0));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            3,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let (_, rewards) = sc.claim_rewards(OptionalValue::None).into_tuple();
+                assert_eq!(rewards.amount, managed_biguint!(31));
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn referral_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let referrer_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+
+    // 10% of the referees' rewards, for 10 epochs
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_referral_program(1_000, 10);
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let new_farm_token = sc.stake_farm_endpoint(
+                    OptionalValue::Some(managed_address!(&farm_setup.user_address)),
+                    OptionalValue::Some(managed_address!(&referrer_addr)),
+                );
+                assert_eq!(new_farm_token.token_nonce, 2);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.stake_farm_endpoint(
+                    OptionalValue::Some(managed_address!(&farm_setup.user_address)),
+                    OptionalValue::Some(managed_address!(&referrer_addr)),
+                );
+            },
+        )
+        .assert_user_error("Referrer may only be set on first stake");
+
+    farm_setup.set_block_nonce(10);
+    let expected_balance = USER_TOTAL_RIDE_TOKENS - farm_in_amount + 47;
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        2,
+        47,
+        &rust_biguint!(expected_balance),
+        &rust_biguint!(expected_balance),
+        3,
+        470_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            let referrer = managed_address!(&referrer_addr);
+            assert_eq!(sc.referral_rewards(&referrer).get(), managed_biguint!(4));
+            assert_eq!(
+                sc.total_referral_earnings(&referrer).get(),
+                managed_biguint!(4)
+            );
+            assert!(sc
+                .referees(&referrer)
+                .contains(&managed_address!(&farm_setup.user_address)));
+        })
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &referrer_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let payment = sc.claim_referral_rewards();
+                assert_eq!(payment.amount, managed_biguint!(4));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&referrer_addr, REWARD_TOKEN_ID, &rust_biguint!(4));
+
+    // the referral period ended
+    farm_setup.set_block_epoch(10);
+    farm_setup.set_block_nonce(20);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            3,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.claim_rewards(OptionalValue::None);
+                let referrer = managed_address!(&referrer_addr);
+                assert_eq!(sc.referral_rewards(&referrer).get(), managed_biguint!(0));
+                assert_eq!(
+                    sc.total_referral_earnings(&referrer).get(),
+                    managed_biguint!(4)
+                );
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn guild_access_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_max_guild_members(1);
+            },
+        )
+        .assert_user_error("Only guild master may manage guild access");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_guild_access_mode(GuildAccessMode::Allowlist);
+                sc.set_max_guild_members(1);
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
+            },
+        )
+        .assert_user_error("Not allowed to join guild");
+
+    let other_user_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+    farm_setup.b_mock.set_esdt_balance(
+        &other_user_addr,
+        FARMING_TOKEN_ID,
+        &rust_biguint!(farm_in_amount),
+    );
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut users = MultiValueEncoded::new();
+                users.push(managed_address!(&farm_setup.user_address));
+                users.push(managed_address!(&other_user_addr));
+                sc.add_to_guild_allowlist(users);
+            },
+        )
+        .assert_ok();
+
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &other_user_addr,
+            &farm_setup.first_farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
+            },
+        )
+        .assert_user_error("Guild is full");
+
+    // positions may not be transferred to new members either
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount / 2),
+            |sc| {
+                sc.transfer_position(managed_address!(&other_user_addr));
+            },
+        )
+        .assert_user_error("Guild is full");
+
+    // members staked before the count was tracked, and the vault, are not counted
+    let legacy_user_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let guild_address = farm_setup.first_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let legacy_user = managed_address!(&legacy_user_addr);
+                sc.user_tokens(&legacy_user).set(managed_biguint!(1_000));
+                sc.remove_tokens(&legacy_user, &managed_biguint!(1_000));
+
+                sc.add_tokens(&managed_address!(&guild_address), &managed_biguint!(1_000));
+                assert_eq!(sc.member_count().get(), 1);
+            },
+        )
+        .assert_ok();
+
+    // invites expire and may be revoked by the guild master
+    farm_setup.set_block_nonce(10);
+    let revoked_signature = [1u8; 64];
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_guild_access_mode(GuildAccessMode::Invite);
+
+                let mut signatures = MultiValueEncoded::new();
+                signatures.push(managed_buffer!(&revoked_signature));
+                sc.revoke_guild_invites(signatures);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &other_user_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.accept_guild_invite(59, managed_buffer!(&revoked_signature));
+            },
+        )
+        .assert_user_error("Invite expired");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &other_user_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.accept_guild_invite(60, managed_buffer!(&revoked_signature));
+            },
+        )
+        .assert_user_error("Invite revoked");
+}
+
+#[test]
+fn unfunded_rewards_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    // the factory has no rewards in the bonus token yet
+    let bonus_token_id = b"BONUS-abcdef";
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut reward_tokens = MultiValueEncoded::new();
+                reward_tokens.push(managed_token_id!(bonus_token_id));
+                sc.add_reward_tokens(reward_tokens);
+            },
+        )
+        .assert_ok();
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.reward_token_id().set(managed_token_id!(bonus_token_id));
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+
+    farm_setup.set_block_nonce(10);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let (_, rewards) = sc.claim_rewards(OptionalValue::None).into_tuple();
+                assert_eq!(rewards.amount, managed_biguint!(0));
+                assert!(sc.unfunded_rewards().get() >= managed_biguint!(47));
+
+                let user = managed_address!(&farm_setup.user_address);
+                assert_eq!(sc.held_back_rewards(&user).get(), managed_biguint!(47));
+            },
+        )
+        .assert_ok();
+
+    // the rewards accrue to the current stakers, only the payout is held back
+    let expected_attributes = StakingFarmTokenAttributes::<DebugApi> {
+        reward_per_share: managed_biguint!(470_000),
+        compounded_reward: managed_biguint!(0),
+        current_farm_amount: managed_biguint!(farm_in_amount),
+        lock: None,
+    };
+    farm_setup.b_mock.check_nft_balance(
+        &farm_setup.user_address,
+        FARM_TOKEN_ID,
+        3,
+        &rust_biguint!(farm_in_amount),
+        Some(&expected_attributes),
+    );
+
+    // closing the guild keeps the deficit
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            |sc| {
+                sc.close_guild();
+            },
+        )
+        .assert_ok();
+
+    let guild_address = farm_setup.first_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            let deficit = sc.get_guild_rewards_deficit(managed_address!(&guild_address));
+            assert!(deficit >= managed_biguint!(47));
+            assert_eq!(
+                sc.total_rewards_deficit(&managed_token_id!(bonus_token_id))
+                    .get(),
+                deficit
+            );
+        })
+        .assert_ok();
+
+    farm_setup.b_mock.set_esdt_balance(
+        &farm_setup.first_owner_address,
+        bonus_token_id,
+        &rust_biguint!(1_000_000),
+    );
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            bonus_token_id,
+            0,
+            &rust_biguint!(1_000_000),
+            |sc| {
+                sc.deposit_rewards_admins();
+            },
+        )
+        .assert_ok();
+
+    // the rewards accrued while unfunded are not lost
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let rewards = sc.claim_held_back_rewards();
+                assert_eq!(rewards.amount, managed_biguint!(47));
+
+                let user = managed_address!(&farm_setup.user_address);
+                assert_eq!(sc.held_back_rewards(&user).get(), managed_biguint!(0));
+                assert_eq!(sc.unfunded_rewards().get(), managed_biguint!(0));
+            },
+        )
+        .assert_ok();
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.user_address,
+        bonus_token_id,
+        &rust_biguint!(47),
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            assert_eq!(
+                sc.get_guild_rewards_deficit(managed_address!(&guild_address)),
+                managed_biguint!(0)
+            );
+            assert_eq!(
+                sc.total_rewards_deficit(&managed_token_id!(bonus_token_id))
+                    .get(),
+                managed_biguint!(0)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn timestamp_accrual_migration_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    // a guild last accrued at block 4, before switching to timestamps
+    farm_setup.set_block_nonce(10);
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.legacy_last_reward_block_nonce().set(4);
+                sc.legacy_internal_seconds_per_block()
+                    .set(SECONDS_PER_BLOCK);
+                sc.legacy_per_block_reward_amount().set(managed_biguint!(
+                    PER_SECOND_REWARD_AMOUNT * SECONDS_PER_BLOCK
+                ));
+
+                sc.upgrade();
+
+                assert_eq!(sc.last_reward_timestamp().get(), 4 * SECONDS_PER_BLOCK);
+                assert!(sc.legacy_last_reward_block_nonce().is_empty());
+                assert!(sc.legacy_internal_seconds_per_block().is_empty());
+                assert!(sc.legacy_per_block_reward_amount().is_empty());
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn legacy_block_emission_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    // the config is upgraded before the guilds
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.per_second_reward_amount().clear();
+                sc.legacy_seconds_per_block().set(SECONDS_PER_BLOCK);
+                sc.legacy_per_block_reward_amount().set(managed_biguint!(
+                    PER_SECOND_REWARD_AMOUNT * SECONDS_PER_BLOCK
+                ));
+
+                sc.upgrade();
+
+                assert_eq!(
+                    sc.per_second_reward_amount().get(),
+                    managed_biguint!(PER_SECOND_REWARD_AMOUNT)
+                );
+                assert_eq!(sc.legacy_seconds_per_block().get(), SECONDS_PER_BLOCK);
+            },
+        )
+        .assert_ok();
+
+    // the guild still runs the block based code, and accrues up to block 8
+    let mut seconds_per_block = 0;
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.config_wrapper, |sc| {
+            seconds_per_block = sc.legacy_seconds_per_block().get();
+        })
+        .assert_ok();
+
+    farm_setup.set_block_nonce(8);
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.legacy_last_reward_block_nonce().set(8);
+                sc.legacy_internal_seconds_per_block()
+                    .set(seconds_per_block);
+            },
+        )
+        .assert_ok();
+
+    // only the two blocks not yet accrued remain after the guild upgrade
+    farm_setup.set_block_nonce(10);
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.upgrade();
+
+                assert_eq!(sc.last_reward_timestamp().get(), 8 * SECONDS_PER_BLOCK);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.clear_legacy_block_emission();
+            },
+        )
+        .assert_user_error("Guilds not upgraded yet");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.current_guild_code_version().set(1);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.clear_legacy_block_emission();
+
+                assert!(sc.legacy_seconds_per_block().is_empty());
+                assert!(sc.legacy_per_block_reward_amount().is_empty());
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn emission_schedule_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut phases = MultiValueEncoded::new();
+                phases.push((30u64, managed_biguint!(1)).into());
+                phases.push((30u64, managed_biguint!(2)).into());
+                sc.set_emission_phases(phases);
+            },
+        )
+        .assert_user_error("Invalid emission schedule");
+
+    // no emission for the first 30 seconds, then one token per second
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut phases = MultiValueEncoded::new();
+                phases.push((0u64, managed_biguint!(0)).into());
+                phases.push((30u64, managed_biguint!(1)).into());
+                sc.set_emission_phases(phases);
+
+                let upcoming: Vec<_> = sc
+                    .get_upcoming_emissions(5)
+                    .into_iter()
+                    .map(|entry| entry.into_tuple())
+                    .collect();
+                assert_eq!(
+                    upcoming,
+                    [(0, managed_biguint!(0)), (30, managed_biguint!(1))]
+                );
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 2;
+    farm_setup.stake_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0);
+
+    // 30 tokens emitted, below the APR bound of 47
+    farm_setup.set_block_nonce(10);
+    let expected_rewards = 30;
+    let expected_farming_token_balance =
+        rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + expected_rewards);
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce,
+        expected_rewards,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 1,
+        300_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_emission_decay(
+                    60,
+                    managed_biguint!(4),
+                    MIN_EMISSION_DECAY_PERIOD_SECONDS - 1,
+                    MAX_PERCENT / 2,
+                    managed_biguint!(1),
+                );
+            },
+        )
+        .assert_user_error("Invalid emission schedule");
+
+    // the floor must be reached within a bounded number of periods
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_emission_decay(
+                    60,
+                    managed_biguint!(1_000_000_000),
+                    MIN_EMISSION_DECAY_PERIOD_SECONDS,
+                    1,
+                    managed_biguint!(0),
+                );
+            },
+        )
+        .assert_user_error("Invalid emission schedule");
+
+    // halves every hour, down to one token per second
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.config_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_emission_decay(
+                    60,
+                    managed_biguint!(4),
+                    MIN_EMISSION_DECAY_PERIOD_SECONDS,
+                    MAX_PERCENT / 2,
+                    managed_biguint!(1),
+                );
+
+                let upcoming: Vec<_> = sc
+                    .get_upcoming_emissions(5)
+                    .into_iter()
+                    .map(|entry| entry.into_tuple())
+                    .collect();
+                assert_eq!(
+                    upcoming,
+                    [
+                        (60, managed_biguint!(4)),
+                        (3_660, managed_biguint!(2)),
+                        (7_260, managed_biguint!(1))
+                    ]
+                );
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn guild_reward_budget_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let guild_address = farm_setup.first_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_reward_request_multiplier(1);
+                sc.set_guild_epoch_allowance(
+                    managed_address!(&guild_address),
+                    OptionalValue::Some(managed_biguint!(30)),
+                );
+            },
+        )
+        .assert_ok();
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 2;
+    farm_setup.stake_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0);
+
+    // only 30 of the 47 rewards may be requested this epoch
+    farm_setup.set_block_nonce(10);
+    let expected_rewards = 30;
+    let expected_farming_token_balance =
+        rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + expected_rewards);
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce,
+        expected_rewards,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 1,
+        470_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            let guild = managed_address!(&guild_address);
+            assert_eq!(
+                sc.get_guild_consumed_rewards(guild.clone()),
+                managed_biguint!(30)
+            );
+            assert_eq!(
+                sc.get_guild_remaining_allowance(guild.clone()),
+                Some(managed_biguint!(0))
+            );
+            assert_eq!(sc.get_guild_rewards_deficit(guild), managed_biguint!(17));
+        })
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.first_farm_wrapper, |sc| {
+            assert_eq!(sc.unfunded_rewards().get(), managed_biguint!(17));
+            assert_eq!(
+                sc.held_back_rewards(&managed_address!(&farm_setup.user_address))
+                    .get(),
+                managed_biguint!(17)
+            );
+        })
+        .assert_ok();
+
+    // the allowance is reset every epoch
+    farm_setup.set_block_epoch(1);
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            assert_eq!(
+                sc.get_guild_remaining_allowance(managed_address!(&guild_address)),
+                Some(managed_biguint!(30))
+            );
+        })
+        .assert_ok();
+
+    // the held back rewards are paid once funded
+    let expected_rewards = 17;
+    let expected_farming_token_balance = expected_farming_token_balance + expected_rewards;
+    farm_setup.claim_rewards(
+        farm_in_amount,
+        expected_farm_token_nonce + 1,
+        expected_rewards,
+        &expected_farming_token_balance,
+        &expected_farming_token_balance,
+        expected_farm_token_nonce + 2,
+        470_000,
+    );
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            assert_eq!(
+                sc.get_guild_rewards_deficit(managed_address!(&guild_address)),
+                managed_biguint!(0)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn delegates_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    let delegate_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+    farm_setup.b_mock.set_esdt_balance(
+        &delegate_addr,
+        FARMING_TOKEN_ID,
+        &rust_biguint!(farm_in_amount),
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut actions = MultiValueEncoded::new();
+                actions.push(DelegateAction::Claim);
+                sc.approve_delegate(managed_address!(&delegate_addr), actions);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &delegate_addr,
+            &farm_setup.first_farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.stake_farm_endpoint(
+                    OptionalValue::Some(managed_address!(&farm_setup.user_address)),
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("May not use original caller arg");
+
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                sc.deposit_delegated_positions();
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &delegate_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.compound_rewards_for(managed_address!(&farm_setup.user_address));
+            },
+        )
+        .assert_user_error("Not an approved delegate");
+
+    // the rewards go to the user, the new position stays in the guild
+    farm_setup.set_block_nonce(10);
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &delegate_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let user = managed_address!(&farm_setup.user_address);
+                let rewards = sc.claim_rewards_for(user.clone());
+                assert_eq!(rewards.amount, managed_biguint!(47));
+
+                let positions = sc.delegated_positions(&user).get();
+                assert_eq!(positions.len(), 1);
+                assert_eq!(positions.get(0).token_nonce, 3);
+            },
+        )
+        .assert_ok();
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.user_address,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount + 47),
+    );
+    farm_setup.b_mock.check_esdt_balance(
+        &delegate_addr,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(farm_in_amount),
+    );
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.revoke_delegate(managed_address!(&delegate_addr));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &delegate_addr,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.claim_rewards_for(managed_address!(&farm_setup.user_address));
+            },
+        )
+        .assert_user_error("Not an approved delegate");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.withdraw_delegated_positions();
+            },
+        )
+        .assert_ok();
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        FARM_TOKEN_ID,
+        3,
+        &rust_biguint!(farm_in_amount),
+        None,
+    );
+
+    // delegates may not set referrers for the user
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut actions = MultiValueEncoded::new();
+                actions.push(DelegateAction::Stake);
+                sc.approve_delegate(managed_address!(&delegate_addr), actions);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &delegate_addr,
+            &farm_setup.first_farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.stake_farm_endpoint(
+                    OptionalValue::Some(managed_address!(&farm_setup.user_address)),
+                    OptionalValue::Some(managed_address!(&delegate_addr)),
+                );
+            },
+        )
+        .assert_user_error("Only the user may set a referrer");
+}
+
+#[test]
+fn claim_rewards_destination_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    let recipient_addr = farm_setup.b_mock.create_user_account(&rust_biguint!(0));
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+
+    // rewards sent to another address, the new farm token goes back to the caller
+    farm_setup.set_block_nonce(10);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let destination = ClaimDestination::Address(managed_address!(&recipient_addr));
+                let (new_farm_token, rewards) = sc
+                    .claim_rewards(OptionalValue::Some(destination))
+                    .into_tuple();
+                assert_eq!(new_farm_token.token_nonce, 3);
+                assert_eq!(rewards.amount, managed_biguint!(47));
+            },
+        )
+        .assert_ok();
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&recipient_addr, REWARD_TOKEN_ID, &rust_biguint!(47));
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.user_address,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount),
+    );
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        FARM_TOKEN_ID,
+        3,
+        &rust_biguint!(farm_in_amount),
+        None,
+    );
+
+    // rewards may not be staked back into the same guild
+    let first_guild_addr = farm_setup.first_farm_wrapper.address_ref().clone();
+    farm_setup.set_block_nonce(20);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            3,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let destination = ClaimDestination::Guild(managed_address!(&first_guild_addr));
+                let _ = sc.claim_rewards(OptionalValue::Some(destination));
+            },
+        )
+        .assert_user_error("Invalid guild");
+
+    // rewards staked in the other guild, with the user as owner of the new position
+    let other_guild_addr = farm_setup.second_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            3,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let destination = ClaimDestination::Guild(managed_address!(&other_guild_addr));
+                let (_, rewards) = sc
+                    .claim_rewards(OptionalValue::Some(destination))
+                    .into_tuple();
+                assert_eq!(rewards.amount, managed_biguint!(47));
+            },
+        )
+        .assert_ok();
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.user_address,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_RIDE_TOKENS - farm_in_amount),
+    );
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        OTHER_FARM_TOKEN_ID,
+        2,
+        &rust_biguint!(47),
+        None,
+    );
+
+    // rewards in other tokens are rejected before claiming
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.reward_token_id().set(managed_token_id!(b"BONUS-abcdef"));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            4,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let destination = ClaimDestination::Guild(managed_address!(&other_guild_addr));
+                let _ = sc.claim_rewards(OptionalValue::Some(destination));
+            },
+        )
+        .assert_user_error("Rewards may only be staked if paid in the farming token");
+}
+
+#[test]
+fn multi_guild_router_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.second_farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.stake_farm_endpoint(OptionalValue::None, OptionalValue::None);
+            },
+        )
+        .assert_ok();
+
+    // farm tokens are resolved to their guilds through the registry
+    let first_guild_addr = farm_setup.first_farm_wrapper.address_ref().clone();
+    let other_guild_addr = farm_setup.second_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            assert_eq!(
+                sc.get_guild_for_farm_token(managed_token_id!(OTHER_FARM_TOKEN_ID)),
+                managed_address!(&other_guild_addr)
+            );
+        })
+        .assert_ok();
+
+    // only the factory may relay actions
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            FARM_TOKEN_ID,
+            2,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.relayed_claim_rewards(managed_address!(&farm_setup.user_address));
+            },
+        )
+        .assert_user_error("Only the factory may relay");
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.factory_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.claim_rewards_multi_guild();
+            },
+        )
+        .assert_user_error("Unknown farm token");
+
+    // claim from both guilds in one transaction
+    farm_setup.set_block_nonce(10);
+    let all_farm_tokens = [
+        TxTokenTransfer {
+            token_identifier: FARM_TOKEN_ID.to_vec(),
+            nonce: 2,
+            value: rust_biguint!(farm_in_amount),
+        },
+        TxTokenTransfer {
+            token_identifier: OTHER_FARM_TOKEN_ID.to_vec(),
+            nonce: 2,
+            value: rust_biguint!(farm_in_amount),
+        },
+    ];
+    farm_setup
+        .b_mock
+        .execute_esdt_multi_transfer(
+            &farm_setup.user_address,
+            &farm_setup.factory_wrapper,
+            &all_farm_tokens,
+            |sc| {
+                let mut results = sc.claim_rewards_multi_guild().into_iter();
+
+                let (guild, new_farm_token, rewards) = results.next().unwrap().into_tuple();
+                assert_eq!(guild, managed_address!(&first_guild_addr));
+                assert_eq!(new_farm_token.token_nonce, 3);
+                assert_eq!(rewards.amount, managed_biguint!(47));
+
+                let (guild, new_farm_token, rewards) = results.next().unwrap().into_tuple();
+                assert_eq!(guild, managed_address!(&other_guild_addr));
+                assert_eq!(new_farm_token.token_nonce, 3);
+                assert_eq!(rewards.amount, managed_biguint!(47));
+
+                assert!(results.next().is_none());
+            },
+        )
+        .assert_ok();
+    farm_setup.b_mock.check_esdt_balance(
+        &farm_setup.user_address,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_RIDE_TOKENS - 2 * farm_in_amount + 2 * 47),
+    );
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        FARM_TOKEN_ID,
+        3,
+        &rust_biguint!(farm_in_amount),
+        None,
+    );
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        OTHER_FARM_TOKEN_ID,
+        3,
+        &rust_biguint!(farm_in_amount),
+        None,
+    );
+
+    // unstake from both guilds, the unbond tokens go to the user
+    let all_farm_tokens = [
+        TxTokenTransfer {
+            token_identifier: FARM_TOKEN_ID.to_vec(),
+            nonce: 3,
+            value: rust_biguint!(farm_in_amount),
+        },
+        TxTokenTransfer {
+            token_identifier: OTHER_FARM_TOKEN_ID.to_vec(),
+            nonce: 3,
+            value: rust_biguint!(farm_in_amount),
+        },
+    ];
+    farm_setup
+        .b_mock
+        .execute_esdt_multi_transfer(
+            &farm_setup.user_address,
+            &farm_setup.factory_wrapper,
+            &all_farm_tokens,
+            |sc| {
+                let results = sc.unstake_farm_multi_guild();
+                for result in results {
+                    let (_, unbond_token, rewards) = result.into_tuple();
+                    assert_eq!(unbond_token.amount, managed_biguint!(farm_in_amount));
+                    assert_eq!(rewards.amount, managed_biguint!(0));
+                }
+            },
+        )
+        .assert_ok();
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        UNBOND_TOKEN_ID,
+        1,
+        &rust_biguint!(farm_in_amount),
+        None,
+    );
+    farm_setup.b_mock.check_nft_balance::<Empty>(
+        &farm_setup.user_address,
+        OTHER_UNBOND_TOKEN_ID,
+        1,
+        &rust_biguint!(farm_in_amount),
+        None,
+    );
+}
+
+#[test]
+fn guild_metadata_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let first_guild_addr = farm_setup.first_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_guild_metadata(GuildMetadata {
+                    name: managed_buffer!(b"Riders"),
+                    description: ManagedBuffer::new(),
+                    website: ManagedBuffer::new(),
+                    social_links: ManagedVec::new(),
+                    logo_uri: ManagedBuffer::new(),
+                    tags: ManagedVec::new(),
+                });
+            },
+        )
+        .assert_user_error("Only guild master may set guild metadata");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_guild_metadata(GuildMetadata {
+                    name: ManagedBuffer::new(),
+                    description: ManagedBuffer::new(),
+                    website: ManagedBuffer::new(),
+                    social_links: ManagedVec::new(),
+                    logo_uri: ManagedBuffer::new(),
+                    tags: ManagedVec::new(),
+                });
+            },
+        )
+        .assert_user_error("Invalid name");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.first_farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut tags = ManagedVec::new();
+                tags.push(managed_buffer!(b"defi"));
+                sc.set_guild_metadata(GuildMetadata {
+                    name: managed_buffer!(b"Riders"),
+                    description: managed_buffer!(b"Long term stakers"),
+                    website: managed_buffer!(b"https://riders.example"),
+                    social_links: ManagedVec::new(),
+                    logo_uri: managed_buffer!(b"https://riders.example/logo.png"),
+                    tags,
+                });
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            let metadata = sc
+                .get_guild_metadata(managed_address!(&first_guild_addr))
+                .unwrap();
+            assert_eq!(metadata.name, managed_buffer!(b"Riders"));
+            assert_eq!(metadata.tags.len(), 1);
+
+            let other_guild = managed_address!(farm_setup.second_farm_wrapper.address_ref());
+            assert!(sc.get_guild_metadata(other_guild).is_none());
+        })
+        .assert_ok();
+
+    // hidden by the admins
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.hide_guild_metadata(managed_address!(&first_guild_addr));
+            },
+        )
+        .assert_user_error("Endpoint can only be called by admins");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.first_owner_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.hide_guild_metadata(managed_address!(&first_guild_addr));
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            let guild = managed_address!(&first_guild_addr);
+            assert!(sc.is_guild_metadata_hidden(guild.clone()));
+            assert!(sc.get_guild_metadata(guild).is_none());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn guild_discovery_test() {
+    DebugApi::dummy();
+
+    let mut farm_setup = FarmStakingSetup::new(
+        guild_sc::contract_obj,
+        guild_sc_config::contract_obj,
+        guild_factory::contract_obj,
+    );
+
+    let farm_in_amount = 100_000_000;
+    farm_setup.stake_farm(farm_in_amount, &[], 2, 0, 0);
+
+    let first_guild_addr = farm_setup.first_farm_wrapper.address_ref().clone();
+    let other_guild_addr = farm_setup.second_farm_wrapper.address_ref().clone();
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let page = sc.get_guilds_page(0, 1, false, managed_biguint!(0));
+                assert_eq!(page.guilds.len(), 1);
+                assert_eq!(page.next_cursor, Some(1));
+
+                let result = page.guilds.get(0);
+                assert_eq!(result.guild, managed_address!(&first_guild_addr));
+                assert_eq!(
+                    result.guild_master,
+                    managed_address!(&farm_setup.first_owner_address)
+                );
+                assert!(result.summary.active);
+                assert!(!result.summary.closing);
+
+                // only the first guild has enough staked
+                let page = sc.get_guilds_page(0, 2, true, managed_biguint!(farm_in_amount));
+                assert_eq!(page.guilds.len(), 1);
+                assert_eq!(
+                    page.guilds.get(0).guild,
+                    managed_address!(&first_guild_addr)
+                );
+                assert_eq!(page.next_cursor, None);
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.user_address,
+            &farm_setup.factory_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.get_guilds_page(0, 0, false, managed_biguint!(0));
+            },
+        )
+        .assert_user_error("Invalid page size");
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &farm_setup.second_owner_address,
+            &farm_setup.second_farm_wrapper,
+            OTHER_FARM_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            |sc| {
+                sc.close_guild();
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.factory_wrapper, |sc| {
+            let page = sc.get_closed_guilds_page(0, 10);
+            assert_eq!(page.guilds.len(), 1);
+            assert_eq!(*page.guilds.get(0), managed_address!(&other_guild_addr));
+            assert_eq!(page.next_cursor, None);
+        })
+        .assert_ok();
+}
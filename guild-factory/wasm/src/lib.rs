@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           18
+// Endpoints:                           50
 // Async Callback (empty):               1
-// Total number of exported functions:  21
+// Total number of exported functions:  53
 
 #![no_std]
 
@@ -27,17 +27,49 @@ multiversx_sc_wasm_adapter::endpoints! {
         resumeGuild => resume_guild_endpoint
         getAllGuilds => get_all_guilds
         getGuildId => get_guild_id
+        getGuildRewardsDeficit => get_guild_rewards_deficit
+        getCurrentGuildCodeVersion => current_guild_code_version
+        getGuildCodeVersion => guild_code_version
+        getGuildsPendingUpgrade => guilds_pending_upgrade
         getRemainingRewards => remaining_rewards
+        getTotalRewardsDeficit => total_rewards_deficit
+        getRewardTokens => reward_tokens
         requestRewards => request_rewards
         migrateToOtherGuild => migrate_to_other_guild
+        stakeRewardsInGuild => stake_rewards_in_guild
         depositRewardsGuild => deposit_rewards_guild
+        depositGuildFees => deposit_guild_fees
         closeGuildNoRewardsRemaining => close_guild_no_rewards_remaining
+        changeGuildMaster => change_guild_master
         depositRewardsAdmins => deposit_rewards_admins
+        addRewardTokens => add_reward_tokens
+        removeRewardTokens => remove_reward_tokens
         getClosedGuilds => closed_guilds
+        hideGuildMetadata => hide_guild_metadata
+        unhideGuildMetadata => unhide_guild_metadata
+        getGuildMetadata => get_guild_metadata
+        isGuildMetadataHidden => is_guild_metadata_hidden
+        setRewardRequestMultiplier => set_reward_request_multiplier
+        setDefaultGuildEpochAllowance => set_default_guild_epoch_allowance
+        setGuildEpochAllowance => set_guild_epoch_allowance
+        getRewardRequestMultiplier => get_reward_request_multiplier
+        getGuildConsumedRewards => get_guild_consumed_rewards
+        getGuildRemainingAllowance => get_guild_remaining_allowance
+        getDefaultGuildEpochAllowance => default_guild_epoch_allowance
+        registerGuildFarmToken => register_guild_farm_token_endpoint
+        claimRewardsMultiGuild => claim_rewards_multi_guild
+        compoundRewardsMultiGuild => compound_rewards_multi_guild
+        unstakeFarmMultiGuild => unstake_farm_multi_guild
+        getGuildForFarmToken => get_guild_for_farm_token
+        getGuildsPage => get_guilds_page
+        getClosedGuildsPage => get_closed_guilds_page
         isAdmin => is_admin
         addAdmin => add_admin
         removeAdmin => remove_admin
         getAdmins => admins
+        setGuildSourceAddress => set_guild_source_address
+        upgradeGuilds => upgrade_guilds
+        getUpgradeCursor => upgrade_cursor
     )
 }
 
@@ -0,0 +1,151 @@
+use crate::{
+    emission::EmissionSchedule,
+    tier_types::{GuildMasterRewardTier, RewardCurve, RewardTier, UserRewardTier},
+};
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+pub type Timestamp = u64;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub enum ConfigChange<M: ManagedTypeApi> {
+    PerSecondRewardAmount(BigUint<M>),
+    TotalStakingTokenMinted(BigUint<M>),
+    GuildMasterTiers(ManagedVec<M, GuildMasterRewardTier<M>>),
+    UserTiers(ManagedVec<M, UserRewardTier>),
+    GuildMasterRewardCurve(RewardCurve),
+    UserRewardCurve(RewardCurve),
+    EmissionSchedule(EmissionSchedule<M>),
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct ConfigCheckpoint<M: ManagedTypeApi> {
+    pub timestamp: Timestamp,
+    pub change: ConfigChange<M>,
+}
+
+/// Every change of a parameter used for accruing rewards is recorded,
+/// so guilds can accrue piecewise between the changes.
+/// Setters apply the scheduled tiers first, so checkpoint timestamps never decrease.
+#[multiversx_sc::module]
+pub trait CheckpointsModule {
+    fn add_config_checkpoint(&self, change: ConfigChange<Self::Api>) {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        self.add_config_checkpoint_at_timestamp(current_timestamp, change);
+    }
+
+    fn add_config_checkpoint_at_timestamp(
+        &self,
+        timestamp: Timestamp,
+        change: ConfigChange<Self::Api>,
+    ) {
+        self.config_checkpoints()
+            .push(&ConfigCheckpoint { timestamp, change });
+    }
+
+    /// Guilds switch to the scheduled tiers at the scheduled timestamp either way.
+    /// This moves them into the active tiers storage and records the checkpoint.
+    #[endpoint(applyScheduledTiers)]
+    fn apply_scheduled_tiers(&self) {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+
+        // the earlier change is applied first, so checkpoints stay ordered
+        let guild_master_timestamp = self.scheduled_guild_master_tiers_timestamp().get();
+        let user_timestamp = self.scheduled_user_tiers_timestamp().get();
+        if user_timestamp < guild_master_timestamp {
+            self.apply_scheduled_user_tiers(current_timestamp);
+            self.apply_scheduled_guild_master_tiers(current_timestamp);
+        } else {
+            self.apply_scheduled_guild_master_tiers(current_timestamp);
+            self.apply_scheduled_user_tiers(current_timestamp);
+        }
+    }
+
+    fn apply_scheduled_guild_master_tiers(&self, current_timestamp: Timestamp) {
+        let timestamp_mapper = self.scheduled_guild_master_tiers_timestamp();
+        if timestamp_mapper.is_empty() || timestamp_mapper.get() > current_timestamp {
+            return;
+        }
+
+        let mut tiers_mapper = self.guild_master_tiers();
+        let mut scheduled_tiers_mapper = self.scheduled_guild_master_tiers();
+        self.move_tiers(&mut tiers_mapper, &mut scheduled_tiers_mapper);
+
+        let scheduled_timestamp = timestamp_mapper.take();
+        self.add_guild_master_tiers_checkpoint(scheduled_timestamp);
+    }
+
+    fn apply_scheduled_user_tiers(&self, current_timestamp: Timestamp) {
+        let timestamp_mapper = self.scheduled_user_tiers_timestamp();
+        if timestamp_mapper.is_empty() || timestamp_mapper.get() > current_timestamp {
+            return;
+        }
+
+        let mut tiers_mapper = self.user_tiers();
+        let mut scheduled_tiers_mapper = self.scheduled_user_tiers();
+        self.move_tiers(&mut tiers_mapper, &mut scheduled_tiers_mapper);
+
+        let scheduled_timestamp = timestamp_mapper.take();
+        self.add_user_tiers_checkpoint(scheduled_timestamp);
+    }
+
+    fn move_tiers<T: TopEncode + TopDecode + RewardTier<Self::Api>>(
+        &self,
+        tiers_mapper: &mut VecMapper<T>,
+        scheduled_tiers_mapper: &mut VecMapper<T>,
+    ) {
+        tiers_mapper.clear();
+        for tier in scheduled_tiers_mapper.iter() {
+            tiers_mapper.push(&tier);
+        }
+
+        scheduled_tiers_mapper.clear();
+    }
+
+    fn add_guild_master_tiers_checkpoint(&self, timestamp: Timestamp) {
+        let mut tiers = ManagedVec::new();
+        for tier in self.guild_master_tiers().iter() {
+            tiers.push(tier);
+        }
+
+        self.add_config_checkpoint_at_timestamp(timestamp, ConfigChange::GuildMasterTiers(tiers));
+    }
+
+    fn add_user_tiers_checkpoint(&self, timestamp: Timestamp) {
+        let mut tiers = ManagedVec::new();
+        for tier in self.user_tiers().iter() {
+            tiers.push(tier);
+        }
+
+        self.add_config_checkpoint_at_timestamp(timestamp, ConfigChange::UserTiers(tiers));
+    }
+
+    #[view(getConfigCheckpoints)]
+    #[storage_mapper("configCheckpoints")]
+    fn config_checkpoints(&self) -> VecMapper<ConfigCheckpoint<Self::Api>>;
+
+    #[view(getGuildMasterTiers)]
+    #[storage_mapper("guildMasterTiers")]
+    fn guild_master_tiers(&self) -> VecMapper<GuildMasterRewardTier<Self::Api>>;
+
+    #[view(getUserTiers)]
+    #[storage_mapper("userTiers")]
+    fn user_tiers(&self) -> VecMapper<UserRewardTier>;
+
+    #[view(getScheduledGuildMasterTiersTimestamp)]
+    #[storage_mapper("scheduledGuildMasterTiersTimestamp")]
+    fn scheduled_guild_master_tiers_timestamp(&self) -> SingleValueMapper<Timestamp>;
+
+    #[view(getScheduledGuildMasterTiers)]
+    #[storage_mapper("scheduledGuildMasterTiers")]
+    fn scheduled_guild_master_tiers(&self) -> VecMapper<GuildMasterRewardTier<Self::Api>>;
+
+    #[view(getScheduledUserTiersTimestamp)]
+    #[storage_mapper("scheduledUserTiersTimestamp")]
+    fn scheduled_user_tiers_timestamp(&self) -> SingleValueMapper<Timestamp>;
+
+    #[view(getScheduledUserTiers)]
+    #[storage_mapper("scheduledUserTiers")]
+    fn scheduled_user_tiers(&self) -> VecMapper<UserRewardTier>;
+}
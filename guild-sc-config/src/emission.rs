@@ -0,0 +1,300 @@
+use common_structs::Percent;
+
+use crate::{
+    checkpoints::{ConfigChange, Timestamp},
+    tiers::MAX_PERCENT,
+};
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+pub type EmissionPhaseMultiValue<M> = MultiValue2<Timestamp, BigUint<M>>;
+
+pub const MAX_EMISSION_PHASES: usize = 20;
+pub const MIN_EMISSION_DECAY_PERIOD_SECONDS: Timestamp = 3_600;
+pub const MAX_EMISSION_DECAY_PERIODS: usize = 100;
+
+pub static INVALID_EMISSION_SCHEDULE_ERR_MSG: &[u8] = b"Invalid emission schedule";
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem, Clone)]
+pub struct EmissionPhase<M: ManagedTypeApi> {
+    pub start_timestamp: Timestamp,
+    pub per_second_amount: BigUint<M>,
+}
+
+/// The per second amount decreases by `decay_percent` every `period_seconds`,
+/// but never goes below `floor_per_second_amount`
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct EmissionDecay<M: ManagedTypeApi> {
+    pub start_timestamp: Timestamp,
+    pub initial_per_second_amount: BigUint<M>,
+    pub period_seconds: Timestamp,
+    pub decay_percent: Percent,
+    pub floor_per_second_amount: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> EmissionDecay<M> {
+    fn decay(&self, amount: &BigUint<M>) -> BigUint<M> {
+        let decayed = amount * (MAX_PERCENT - self.decay_percent) / MAX_PERCENT;
+        core::cmp::max(decayed, self.floor_per_second_amount.clone())
+    }
+
+    /// Keeps the decay steps needed to compute the amount of any period bounded
+    fn reaches_floor_within(&self, max_periods: usize) -> bool {
+        let mut amount = self.initial_per_second_amount.clone();
+        for _ in 0..max_periods {
+            if amount == self.floor_per_second_amount {
+                return true;
+            }
+
+            amount = self.decay(&amount);
+        }
+
+        amount == self.floor_per_second_amount
+    }
+
+    /// The period containing the timestamp and its per second amount
+    fn period_at(&self, timestamp: Timestamp) -> (Timestamp, BigUint<M>) {
+        let period = (timestamp - self.start_timestamp) / self.period_seconds;
+        let mut amount = self.initial_per_second_amount.clone();
+        for _ in 0..period {
+            if amount == self.floor_per_second_amount {
+                break;
+            }
+
+            amount = self.decay(&amount);
+        }
+
+        (period, amount)
+    }
+}
+
+/// Before the schedule starts, the manually set per second amount is used
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub enum EmissionSchedule<M: ManagedTypeApi> {
+    Manual,
+    Phases(ManagedVec<M, EmissionPhase<M>>),
+    Decay(EmissionDecay<M>),
+}
+
+impl<M: ManagedTypeApi> EmissionSchedule<M> {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            EmissionSchedule::Manual => true,
+            EmissionSchedule::Phases(phases) => {
+                if phases.is_empty() || phases.len() > MAX_EMISSION_PHASES {
+                    return false;
+                }
+
+                let mut opt_prev_start = None;
+                for phase in phases {
+                    if opt_prev_start.is_some_and(|prev_start| phase.start_timestamp <= prev_start)
+                    {
+                        return false;
+                    }
+
+                    opt_prev_start = Some(phase.start_timestamp);
+                }
+
+                true
+            }
+            EmissionSchedule::Decay(decay) => {
+                decay.period_seconds >= MIN_EMISSION_DECAY_PERIOD_SECONDS
+                    && decay.decay_percent > 0
+                    && decay.decay_percent < MAX_PERCENT
+                    && decay.floor_per_second_amount <= decay.initial_per_second_amount
+                    && decay.reaches_floor_within(MAX_EMISSION_DECAY_PERIODS)
+            }
+        }
+    }
+
+    pub fn per_second_amount_at(
+        &self,
+        timestamp: Timestamp,
+        manual_amount: &BigUint<M>,
+    ) -> BigUint<M> {
+        match self {
+            EmissionSchedule::Manual => manual_amount.clone(),
+            EmissionSchedule::Phases(phases) => {
+                let mut amount = manual_amount.clone();
+                for phase in phases {
+                    if phase.start_timestamp > timestamp {
+                        break;
+                    }
+
+                    amount = phase.per_second_amount;
+                }
+
+                amount
+            }
+            EmissionSchedule::Decay(decay) => {
+                if timestamp < decay.start_timestamp {
+                    return manual_amount.clone();
+                }
+
+                let (_, amount) = decay.period_at(timestamp);
+                amount
+            }
+        }
+    }
+
+    /// First timestamp after the given one where the per second amount changes
+    pub fn next_change_after(&self, timestamp: Timestamp) -> Option<Timestamp> {
+        match self {
+            EmissionSchedule::Manual => None,
+            EmissionSchedule::Phases(phases) => phases
+                .iter()
+                .map(|phase| phase.start_timestamp)
+                .find(|start_timestamp| *start_timestamp > timestamp),
+            EmissionSchedule::Decay(decay) => {
+                if timestamp < decay.start_timestamp {
+                    return Some(decay.start_timestamp);
+                }
+
+                let (period, amount) = decay.period_at(timestamp);
+                if amount == decay.floor_per_second_amount {
+                    return None;
+                }
+
+                Some(decay.start_timestamp + (period + 1) * decay.period_seconds)
+            }
+        }
+    }
+
+    /// Total amount emitted in [start_timestamp, end_timestamp), across all the phase boundaries
+    pub fn amount_between(
+        &self,
+        start_timestamp: Timestamp,
+        end_timestamp: Timestamp,
+        manual_amount: &BigUint<M>,
+    ) -> BigUint<M> {
+        let mut total = BigUint::zero();
+        let mut segment_start = start_timestamp;
+        if let EmissionSchedule::Decay(decay) = self {
+            if segment_start < decay.start_timestamp {
+                let manual_end = core::cmp::min(decay.start_timestamp, end_timestamp);
+                total += manual_amount * (manual_end - segment_start);
+                segment_start = manual_end;
+            }
+            if segment_start >= end_timestamp {
+                return total;
+            }
+
+            // the amount is decayed step by step, instead of recomputing it for each period
+            let (mut period, mut amount) = decay.period_at(segment_start);
+            while segment_start < end_timestamp {
+                if amount == decay.floor_per_second_amount {
+                    return total + amount * (end_timestamp - segment_start);
+                }
+
+                let period_end = decay.start_timestamp + (period + 1) * decay.period_seconds;
+                let segment_end = core::cmp::min(period_end, end_timestamp);
+                total += &amount * (segment_end - segment_start);
+
+                segment_start = segment_end;
+                amount = decay.decay(&amount);
+                period += 1;
+            }
+
+            return total;
+        }
+
+        while segment_start < end_timestamp {
+            let amount = self.per_second_amount_at(segment_start, manual_amount);
+            let segment_end = match self.next_change_after(segment_start) {
+                Some(next_change) => core::cmp::min(next_change, end_timestamp),
+                None => end_timestamp,
+            };
+            total += amount * (segment_end - segment_start);
+            segment_start = segment_end;
+        }
+
+        total
+    }
+}
+
+/// Emission that changes over time without manual transactions,
+/// either through fixed phases or through a decay curve
+#[multiversx_sc::module]
+pub trait EmissionModule:
+    crate::global_config::GlobalConfigModule + crate::checkpoints::CheckpointsModule
+{
+    /// Pairs of (start_timestamp, per_second_amount), in increasing start order
+    #[only_owner]
+    #[endpoint(setEmissionPhases)]
+    fn set_emission_phases(&self, phases: MultiValueEncoded<EmissionPhaseMultiValue<Self::Api>>) {
+        let mut phases_vec = ManagedVec::new();
+        for phase in phases {
+            let (start_timestamp, per_second_amount) = phase.into_tuple();
+            phases_vec.push(EmissionPhase {
+                start_timestamp,
+                per_second_amount,
+            });
+        }
+
+        self.set_emission_schedule(EmissionSchedule::Phases(phases_vec));
+    }
+
+    #[only_owner]
+    #[endpoint(setEmissionDecay)]
+    fn set_emission_decay(
+        &self,
+        start_timestamp: Timestamp,
+        initial_per_second_amount: BigUint,
+        period_seconds: Timestamp,
+        decay_percent: Percent,
+        floor_per_second_amount: BigUint,
+    ) {
+        self.set_emission_schedule(EmissionSchedule::Decay(EmissionDecay {
+            start_timestamp,
+            initial_per_second_amount,
+            period_seconds,
+            decay_percent,
+            floor_per_second_amount,
+        }));
+    }
+
+    /// Goes back to the per second amount set through setPerSecondRewardAmount
+    #[only_owner]
+    #[endpoint(clearEmissionSchedule)]
+    fn clear_emission_schedule(&self) {
+        self.set_emission_schedule(EmissionSchedule::Manual);
+    }
+
+    fn set_emission_schedule(&self, schedule: EmissionSchedule<Self::Api>) {
+        self.apply_scheduled_tiers();
+
+        require!(schedule.is_valid(), INVALID_EMISSION_SCHEDULE_ERR_MSG);
+
+        self.emission_schedule().set(&schedule);
+        self.add_config_checkpoint(ConfigChange::EmissionSchedule(schedule));
+    }
+
+    /// Pairs of (timestamp, per_second_amount), starting with the current amount
+    #[view(getUpcomingEmissions)]
+    fn get_upcoming_emissions(
+        &self,
+        max_entries: usize,
+    ) -> MultiValueEncoded<EmissionPhaseMultiValue<Self::Api>> {
+        let schedule = self.emission_schedule().get();
+        let manual_amount = self.per_second_reward_amount().get();
+        let mut timestamp = self.blockchain().get_block_timestamp();
+
+        let mut result = MultiValueEncoded::new();
+        while result.len() < max_entries {
+            let amount = schedule.per_second_amount_at(timestamp, &manual_amount);
+            result.push((timestamp, amount).into());
+
+            match schedule.next_change_after(timestamp) {
+                Some(next_change) => timestamp = next_change,
+                None => break,
+            }
+        }
+
+        result
+    }
+
+    #[view(getEmissionSchedule)]
+    #[storage_mapper("emissionSchedule")]
+    fn emission_schedule(&self) -> SingleValueMapper<EmissionSchedule<Self::Api>>;
+}
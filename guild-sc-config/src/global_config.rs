@@ -1,7 +1,13 @@
-use common_structs::Epoch;
+use common_structs::{Epoch, Percent};
 use multiversx_sc::storage::StorageKey;
 
+use crate::{
+    checkpoints::{ConfigChange, Timestamp},
+    tiers::MAX_PERCENT,
+};
+
 multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
 
 pub static INVALID_MIN_UNBOND_EPOCHS_ERR_MSG: &[u8] = b"Invalid min unbond epochs";
 static INVALID_VALUE_ERR_MSG: &[u8] = b"Invalid value";
@@ -9,6 +15,8 @@ static INVALID_VALUE_ERR_MSG: &[u8] = b"Invalid value";
 static GUILD_SC_ID_STORAGE_KEY: &[u8] = b"deployedGuilds";
 static GUILD_ADDRESS_TO_ID_MAPPER_STORAGE_KEY: &[u8] = b"guildIds";
 static CLOSED_GUILDS_MAPPER_STORAGE_KEY: &[u8] = b"closedGuilds";
+static CURRENT_GUILD_CODE_VERSION_STORAGE_KEY: &[u8] = b"currentGuildCodeVersion";
+static GUILDS_PENDING_UPGRADE_STORAGE_KEY: &[u8] = b"guildsPendingUpgrade";
 
 pub const MAX_MIN_UNBOND_EPOCHS: Epoch = 30;
 
@@ -16,8 +24,17 @@ pub type GlobalPauseStatus = bool;
 pub const PAUSED: bool = true;
 pub const UNPAUSED: bool = false;
 
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct BoostedYieldsFactors<M: ManagedTypeApi> {
+    pub max_rewards_factor: BigUint<M>,
+    pub user_rewards_energy_const: BigUint<M>,
+    pub user_rewards_farm_const: BigUint<M>,
+    pub min_energy_amount: BigUint<M>,
+    pub min_farm_amount: BigUint<M>,
+}
+
 #[multiversx_sc::module]
-pub trait GlobalConfigModule {
+pub trait GlobalConfigModule: crate::checkpoints::CheckpointsModule {
     #[only_owner]
     #[endpoint(setMinStakeUser)]
     fn set_min_stake_user(&self, min_stake: BigUint) {
@@ -33,7 +50,10 @@ pub trait GlobalConfigModule {
     #[only_owner]
     #[endpoint(setTotalStakingTokenMinted)]
     fn set_total_staking_token_minted(&self, total_minted: BigUint) {
-        self.total_staking_token_minted().set(total_minted);
+        self.apply_scheduled_tiers();
+
+        self.total_staking_token_minted().set(&total_minted);
+        self.add_config_checkpoint(ConfigChange::TotalStakingTokenMinted(total_minted));
     }
 
     #[endpoint(increaseStakedTokens)]
@@ -53,20 +73,56 @@ pub trait GlobalConfigModule {
     }
 
     #[only_owner]
-    #[endpoint(setSecondsPerBlock)]
-    fn set_seconds_per_block(&self, new_seconds_per_block: u64) {
-        require!(new_seconds_per_block > 0, INVALID_VALUE_ERR_MSG);
+    #[endpoint(setPerSecondRewardAmount)]
+    fn set_per_second_reward_amount(&self, new_per_second_reward_amount: BigUint) {
+        self.apply_scheduled_tiers();
+
+        require!(new_per_second_reward_amount > 0, INVALID_VALUE_ERR_MSG);
+
+        self.per_second_reward_amount()
+            .set(&new_per_second_reward_amount);
+        self.add_config_checkpoint(ConfigChange::PerSecondRewardAmount(
+            new_per_second_reward_amount,
+        ));
+    }
+
+    #[only_owner]
+    #[endpoint(setBoostedYieldsFactors)]
+    fn set_boosted_yields_factors(
+        &self,
+        max_rewards_factor: BigUint,
+        user_rewards_energy_const: BigUint,
+        user_rewards_farm_const: BigUint,
+        min_energy_amount: BigUint,
+        min_farm_amount: BigUint,
+    ) {
+        require!(
+            max_rewards_factor > 0
+                && user_rewards_energy_const > 0
+                && user_rewards_farm_const > 0
+                && min_energy_amount > 0
+                && min_farm_amount > 0,
+            INVALID_VALUE_ERR_MSG
+        );
 
-        self.seconds_per_block().set(new_seconds_per_block);
+        self.boosted_yields_factors().set(BoostedYieldsFactors {
+            max_rewards_factor,
+            user_rewards_energy_const,
+            user_rewards_farm_const,
+            min_energy_amount,
+            min_farm_amount,
+        });
     }
 
     #[only_owner]
-    #[endpoint(setPerBlockRewardAmount)]
-    fn set_per_block_reward_amount(&self, new_per_block_reward_amount: BigUint) {
-        require!(new_per_block_reward_amount > 0, INVALID_VALUE_ERR_MSG);
+    #[endpoint(setEnergyFactoryAddress)]
+    fn set_energy_factory_address(&self, sc_address: ManagedAddress) {
+        require!(
+            self.blockchain().is_smart_contract(&sc_address),
+            INVALID_VALUE_ERR_MSG
+        );
 
-        self.per_block_reward_amount()
-            .set(new_per_block_reward_amount);
+        self.energy_factory_address().set(sc_address);
     }
 
     #[only_owner]
@@ -81,6 +137,45 @@ pub trait GlobalConfigModule {
         self.global_pause_status().set(UNPAUSED);
     }
 
+    /// Guilds already above the new maximum are capped at it
+    #[only_owner]
+    #[endpoint(setMaxGuildMasterCommission)]
+    fn set_max_guild_master_commission(&self, max_commission: Percent) {
+        require!(max_commission <= MAX_PERCENT, INVALID_VALUE_ERR_MSG);
+
+        self.max_guild_master_commission().set(max_commission);
+    }
+
+    /// Number of seconds until a guild master's commission increase applies
+    #[only_owner]
+    #[endpoint(setCommissionNoticeSeconds)]
+    fn set_commission_notice_seconds(&self, notice_seconds: Timestamp) {
+        self.commission_notice_seconds().set(notice_seconds);
+    }
+
+    /// Guilds running the block based code still read the legacy emission keys.
+    /// They may only be cleared once the factory upgraded every guild to a newer code version.
+    #[only_owner]
+    #[endpoint(clearLegacyBlockEmission)]
+    fn clear_legacy_block_emission(&self) {
+        let version_mapper = self.legacy_emission_guild_code_version();
+        require!(!version_mapper.is_empty(), "No legacy emission");
+
+        let factory_sc = self.blockchain().get_owner_address();
+        let current_guild_code_version = self
+            .get_current_guild_code_version_mapper(factory_sc.clone())
+            .get();
+        let guilds_pending_upgrade = self.get_guilds_pending_upgrade_mapper(factory_sc).get();
+        require!(
+            current_guild_code_version > version_mapper.get() && guilds_pending_upgrade == 0,
+            "Guilds not upgraded yet"
+        );
+
+        version_mapper.clear();
+        self.legacy_seconds_per_block().clear();
+        self.legacy_per_block_reward_amount().clear();
+    }
+
     fn set_min_unbond_epochs_user(&self, min_unbond_epochs: Epoch) {
         self.require_valid_unbond_epochs(min_unbond_epochs);
 
@@ -93,6 +188,22 @@ pub trait GlobalConfigModule {
         self.min_unbond_epochs_guild_master().set(min_unbond_epochs);
     }
 
+    /// Fee for unbonding instead of waiting the full user unbond period.
+    /// It decays linearly with the remaining epochs.
+    #[only_owner]
+    #[endpoint(setInstantUnbondFee)]
+    fn set_instant_unbond_fee(&self, max_fee: Percent) {
+        require!(max_fee <= MAX_PERCENT, INVALID_VALUE_ERR_MSG);
+
+        self.instant_unbond_fee().set(max_fee);
+    }
+
+    #[only_owner]
+    #[endpoint(disableInstantUnbond)]
+    fn disable_instant_unbond(&self) {
+        self.instant_unbond_fee().clear();
+    }
+
     fn require_valid_unbond_epochs(&self, unbond_epochs: Epoch) {
         require!(
             unbond_epochs <= MAX_MIN_UNBOND_EPOCHS,
@@ -137,6 +248,26 @@ pub trait GlobalConfigModule {
         )
     }
 
+    fn get_current_guild_code_version_mapper(
+        &self,
+        factory_sc: ManagedAddress,
+    ) -> SingleValueMapper<u32, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            factory_sc,
+            StorageKey::new(CURRENT_GUILD_CODE_VERSION_STORAGE_KEY),
+        )
+    }
+
+    fn get_guilds_pending_upgrade_mapper(
+        &self,
+        factory_sc: ManagedAddress,
+    ) -> SingleValueMapper<usize, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            factory_sc,
+            StorageKey::new(GUILDS_PENDING_UPGRADE_STORAGE_KEY),
+        )
+    }
+
     fn get_guild_address_to_id_mapper(
         &self,
         factory_sc: ManagedAddress,
@@ -159,6 +290,10 @@ pub trait GlobalConfigModule {
     #[storage_mapper("minUnbondEpochsGuildMaster")]
     fn min_unbond_epochs_guild_master(&self) -> SingleValueMapper<Epoch>;
 
+    #[view(getInstantUnbondFee)]
+    #[storage_mapper("instantUnbondFee")]
+    fn instant_unbond_fee(&self) -> SingleValueMapper<Percent>;
+
     #[view(getMinStakeUser)]
     #[storage_mapper("minStakeUser")]
     fn min_stake_user(&self) -> SingleValueMapper<BigUint>;
@@ -191,15 +326,41 @@ pub trait GlobalConfigModule {
     #[storage_mapper("tokensDecimals")]
     fn tokens_decimals(&self) -> SingleValueMapper<usize>;
 
+    #[view(getPerSecondRewardAmount)]
+    #[storage_mapper("perSecondRewardAmount")]
+    fn per_second_reward_amount(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getBoostedYieldsFactors)]
+    #[storage_mapper("boostedYieldsFactors")]
+    fn boosted_yields_factors(&self) -> SingleValueMapper<BoostedYieldsFactors<Self::Api>>;
+
+    #[view(getEnergyFactoryAddress)]
+    #[storage_mapper("energyFactoryAddress")]
+    fn energy_factory_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(areAllGuildsPaused)]
+    #[storage_mapper("globalPauseStatus")]
+    fn global_pause_status(&self) -> SingleValueMapper<GlobalPauseStatus>;
+
+    #[view(getMaxGuildMasterCommission)]
+    #[storage_mapper("maxGuildMasterCommission")]
+    fn max_guild_master_commission(&self) -> SingleValueMapper<Percent>;
+
+    #[view(getCommissionNoticeSeconds)]
+    #[storage_mapper("commissionNoticeSeconds")]
+    fn commission_notice_seconds(&self) -> SingleValueMapper<Timestamp>;
+
+    /// Block based emission, still read by the guilds not yet upgraded
     #[view(getSecondsPerBlock)]
     #[storage_mapper("secondsPerBlock")]
-    fn seconds_per_block(&self) -> SingleValueMapper<u64>;
+    fn legacy_seconds_per_block(&self) -> SingleValueMapper<u64>;
 
+    /// Block based emission, still read by the guilds not yet upgraded
     #[view(getPerBlockRewardAmount)]
     #[storage_mapper("perBlockRewardAmount")]
-    fn per_block_reward_amount(&self) -> SingleValueMapper<BigUint>;
+    fn legacy_per_block_reward_amount(&self) -> SingleValueMapper<BigUint>;
 
-    #[view(areAllGuildsPaused)]
-    #[storage_mapper("globalPauseStatus")]
-    fn global_pause_status(&self) -> SingleValueMapper<GlobalPauseStatus>;
+    /// Factory guild code version when the config switched to per second emission
+    #[storage_mapper("legacyEmissionGuildCodeVersion")]
+    fn legacy_emission_guild_code_version(&self) -> SingleValueMapper<u32>;
 }
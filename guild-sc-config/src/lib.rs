@@ -5,7 +5,11 @@ use common_structs::Epoch;
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
+pub mod checkpoints;
+pub mod emission;
 pub mod global_config;
+pub mod lock_options;
+pub mod scheduled_tiers;
 pub mod tier_types;
 pub mod tiers;
 
@@ -21,12 +25,18 @@ pub struct InitArgs<M: ManagedTypeApi> {
     pub base_unbond_token_id: ManagedBuffer<M>,
     pub base_token_display_name: ManagedBuffer<M>,
     pub tokens_decimals: usize,
-    pub seconds_per_block: u64,
-    pub per_block_reward_amount: BigUint<M>,
+    pub per_second_reward_amount: BigUint<M>,
 }
 
 #[multiversx_sc::contract]
-pub trait GuildScConfig: tiers::TierModule + global_config::GlobalConfigModule {
+pub trait GuildScConfig:
+    tiers::TierModule
+    + global_config::GlobalConfigModule
+    + checkpoints::CheckpointsModule
+    + scheduled_tiers::ScheduledTiersModule
+    + lock_options::LockOptionsModule
+    + emission::EmissionModule
+{
     #[init]
     fn init(&self, args: InitArgs<Self::Api>) {
         self.set_total_staking_token_minted(args.total_staking_tokens_minted);
@@ -34,8 +44,7 @@ pub trait GuildScConfig: tiers::TierModule + global_config::GlobalConfigModule {
         self.set_min_unbond_epochs_guild_master(args.guild_master_unbond_epochs);
         self.set_min_stake_user(args.min_stake_user);
         self.set_min_stake_guild_master(args.min_stake_guild_master);
-        self.set_seconds_per_block(args.seconds_per_block);
-        self.set_per_block_reward_amount(args.per_block_reward_amount);
+        self.set_per_second_reward_amount(args.per_second_reward_amount);
         self.set_max_staked_tokens(args.max_staked_tokens);
 
         self.base_farm_token_id().set(args.base_farm_token_id);
@@ -46,5 +55,26 @@ pub trait GuildScConfig: tiers::TierModule + global_config::GlobalConfigModule {
     }
 
     #[upgrade]
-    fn upgrade(&self) {}
+    fn upgrade(&self) {
+        self.migrate_to_per_second_emission();
+    }
+
+    /// The block based emission keys stay readable for the guilds not yet upgraded.
+    /// They are cleared through `clearLegacyBlockEmission` once every guild was upgraded.
+    fn migrate_to_per_second_emission(&self) {
+        let seconds_per_block_mapper = self.legacy_seconds_per_block();
+        if seconds_per_block_mapper.is_empty() || !self.per_second_reward_amount().is_empty() {
+            return;
+        }
+
+        let seconds_per_block = seconds_per_block_mapper.get();
+        let per_block_reward_amount = self.legacy_per_block_reward_amount().get();
+        self.per_second_reward_amount()
+            .set(per_block_reward_amount / seconds_per_block);
+
+        let factory_sc = self.blockchain().get_owner_address();
+        let guild_code_version = self.get_current_guild_code_version_mapper(factory_sc).get();
+        self.legacy_emission_guild_code_version()
+            .set(guild_code_version);
+    }
 }
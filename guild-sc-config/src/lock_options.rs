@@ -0,0 +1,63 @@
+use common_structs::{Epoch, Percent};
+
+use crate::tiers::MAX_PERCENT;
+
+multiversx_sc::imports!();
+
+pub type LockOptionMultiValue = MultiValue2<Epoch, Percent>;
+
+pub const MAX_LOCK_MULTIPLIER: Percent = 3 * MAX_PERCENT;
+
+pub static INVALID_LOCK_OPTION_ERR_MSG: &[u8] = b"Invalid lock option";
+
+/// Durations users may lock their guild positions for.
+/// Locked positions have their reward share weighted by the option's multiplier.
+#[multiversx_sc::module]
+pub trait LockOptionsModule {
+    /// Pairs of (lock_epochs, multiplier), where MAX_PERCENT is a multiplier of 1
+    #[only_owner]
+    #[endpoint(addLockOptions)]
+    fn add_lock_options(&self, options: MultiValueEncoded<LockOptionMultiValue>) {
+        let mut lock_options_mapper = self.lock_options();
+        for option in options {
+            let (lock_epochs, multiplier) = option.into_tuple();
+            require!(
+                lock_epochs > 0 && multiplier > MAX_PERCENT && multiplier <= MAX_LOCK_MULTIPLIER,
+                INVALID_LOCK_OPTION_ERR_MSG
+            );
+
+            let _ = lock_options_mapper.insert(lock_epochs);
+            self.lock_multiplier(lock_epochs).set(multiplier);
+        }
+    }
+
+    /// Existing locks keep their multiplier until they expire
+    #[only_owner]
+    #[endpoint(removeLockOptions)]
+    fn remove_lock_options(&self, lock_epochs: MultiValueEncoded<Epoch>) {
+        let mut lock_options_mapper = self.lock_options();
+        for epochs in lock_epochs {
+            let removed = lock_options_mapper.swap_remove(&epochs);
+            require!(removed, INVALID_LOCK_OPTION_ERR_MSG);
+
+            self.lock_multiplier(epochs).clear();
+        }
+    }
+
+    #[view(getLockOptions)]
+    fn get_lock_options(&self) -> MultiValueEncoded<LockOptionMultiValue> {
+        let mut result = MultiValueEncoded::new();
+        for lock_epochs in self.lock_options().iter() {
+            let multiplier = self.lock_multiplier(lock_epochs).get();
+            result.push((lock_epochs, multiplier).into());
+        }
+
+        result
+    }
+
+    #[storage_mapper("lockOptions")]
+    fn lock_options(&self) -> UnorderedSetMapper<Epoch>;
+
+    #[storage_mapper("lockMultiplier")]
+    fn lock_multiplier(&self, lock_epochs: Epoch) -> SingleValueMapper<Percent>;
+}
@@ -0,0 +1,90 @@
+use crate::checkpoints::Timestamp;
+use crate::tier_types::{GuildMasterRewardTierMultiValue, UserRewardTierMultiValue};
+
+multiversx_sc::imports!();
+
+pub static CHANGE_ALREADY_SCHEDULED_ERR_MSG: &[u8] = b"Tiers change already scheduled";
+pub static NO_CHANGE_SCHEDULED_ERR_MSG: &[u8] = b"No tiers change scheduled";
+
+/// Tier changes that all the guilds apply at the same timestamp.
+/// Only one change per track may be scheduled at a time.
+#[multiversx_sc::module]
+pub trait ScheduledTiersModule:
+    crate::tiers::TierModule
+    + crate::global_config::GlobalConfigModule
+    + crate::checkpoints::CheckpointsModule
+{
+    /// Same arguments as updateGuildMasterTiers
+    #[only_owner]
+    #[endpoint(scheduleGuildMasterTiersUpdate)]
+    fn schedule_guild_master_tiers_update(
+        &self,
+        effective_timestamp: Timestamp,
+        tiers: MultiValueEncoded<GuildMasterRewardTierMultiValue<Self::Api>>,
+    ) {
+        self.apply_scheduled_tiers();
+
+        let timestamp_mapper = self.scheduled_guild_master_tiers_timestamp();
+        require!(
+            timestamp_mapper.is_empty(),
+            CHANGE_ALREADY_SCHEDULED_ERR_MSG
+        );
+        self.require_future_timestamp(effective_timestamp);
+
+        let mut tiers_mapper = self.scheduled_guild_master_tiers();
+        self.set_guild_master_tiers_common(&mut tiers_mapper, tiers);
+        timestamp_mapper.set(effective_timestamp);
+    }
+
+    /// Same arguments as updateUserTiers
+    #[only_owner]
+    #[endpoint(scheduleUserTiersUpdate)]
+    fn schedule_user_tiers_update(
+        &self,
+        effective_timestamp: Timestamp,
+        tiers: MultiValueEncoded<UserRewardTierMultiValue>,
+    ) {
+        self.apply_scheduled_tiers();
+
+        let timestamp_mapper = self.scheduled_user_tiers_timestamp();
+        require!(
+            timestamp_mapper.is_empty(),
+            CHANGE_ALREADY_SCHEDULED_ERR_MSG
+        );
+        self.require_future_timestamp(effective_timestamp);
+
+        let mut tiers_mapper = self.scheduled_user_tiers();
+        self.set_user_tiers_common(&mut tiers_mapper, tiers);
+        timestamp_mapper.set(effective_timestamp);
+    }
+
+    #[only_owner]
+    #[endpoint(cancelScheduledGuildMasterTiersUpdate)]
+    fn cancel_scheduled_guild_master_tiers_update(&self) {
+        let timestamp_mapper = self.scheduled_guild_master_tiers_timestamp();
+        require!(!timestamp_mapper.is_empty(), NO_CHANGE_SCHEDULED_ERR_MSG);
+        self.require_future_timestamp(timestamp_mapper.get());
+
+        timestamp_mapper.clear();
+        self.scheduled_guild_master_tiers().clear();
+    }
+
+    #[only_owner]
+    #[endpoint(cancelScheduledUserTiersUpdate)]
+    fn cancel_scheduled_user_tiers_update(&self) {
+        let timestamp_mapper = self.scheduled_user_tiers_timestamp();
+        require!(!timestamp_mapper.is_empty(), NO_CHANGE_SCHEDULED_ERR_MSG);
+        self.require_future_timestamp(timestamp_mapper.get());
+
+        timestamp_mapper.clear();
+        self.scheduled_user_tiers().clear();
+    }
+
+    fn require_future_timestamp(&self, timestamp: Timestamp) {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        require!(
+            timestamp > current_timestamp,
+            "Timestamp must be in the future"
+        );
+    }
+}
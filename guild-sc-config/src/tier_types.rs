@@ -8,7 +8,7 @@ multiversx_sc::derive_imports!();
 pub type GuildMasterRewardTierMultiValue<M> = MultiValue2<BigUint<M>, Percent>;
 pub type UserRewardTierMultiValue = MultiValue2<Percent, Percent>;
 
-#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem)]
 pub struct GuildMasterRewardTier<M: ManagedTypeApi> {
     pub max_stake: BigUint<M>,
     pub apr: Percent,
@@ -25,7 +25,7 @@ impl<M: ManagedTypeApi> From<GuildMasterRewardTierMultiValue<M>> for GuildMaster
     }
 }
 
-#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem)]
 pub struct UserRewardTier {
     pub max_percentage_staked: Percent,
     pub apr: Percent,
@@ -42,6 +42,36 @@ impl From<UserRewardTierMultiValue> for UserRewardTier {
     }
 }
 
+/// APR = numerator / staked_ratio, bounded by min_apr and max_apr
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct InverseStakedRatioParams {
+    pub numerator: u64,
+    pub min_apr: Percent,
+    pub max_apr: Percent,
+}
+
+impl InverseStakedRatioParams {
+    pub fn is_valid(&self) -> bool {
+        self.numerator > 0 && self.min_apr <= self.max_apr
+    }
+
+    pub fn get_apr(&self, staked_ratio: Percent) -> Percent {
+        if staked_ratio == 0 {
+            return self.max_apr;
+        }
+
+        let apr = self.numerator / staked_ratio;
+        apr.clamp(self.min_apr, self.max_apr)
+    }
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub enum RewardCurve {
+    Step,
+    PiecewiseLinear,
+    InverseStakedRatio(InverseStakedRatioParams),
+}
+
 pub trait RewardTier<M: ManagedTypeApi> {
     fn is_in_range(&self, user_stake: &BigUint<M>, percentage_staked: Percent) -> bool;
 
@@ -49,6 +79,8 @@ pub trait RewardTier<M: ManagedTypeApi> {
 
     fn is_equal(&self, other: &Self) -> bool;
 
+    fn get_max_value(&self) -> BigUint<M>;
+
     fn get_apr(&self) -> Percent;
 
     fn set_apr(&mut self, other: &Self);
@@ -67,6 +99,10 @@ impl<M: ManagedTypeApi> RewardTier<M> for GuildMasterRewardTier<M> {
         self.max_stake == other.max_stake
     }
 
+    fn get_max_value(&self) -> BigUint<M> {
+        self.max_stake.clone()
+    }
+
     fn get_apr(&self) -> Percent {
         self.apr
     }
@@ -89,6 +125,10 @@ impl<M: ManagedTypeApi> RewardTier<M> for UserRewardTier {
         self.max_percentage_staked == other.max_percentage_staked
     }
 
+    fn get_max_value(&self) -> BigUint<M> {
+        BigUint::from(self.max_percentage_staked)
+    }
+
     fn get_apr(&self) -> Percent {
         self.apr
     }
@@ -1,8 +1,9 @@
 use common_structs::Percent;
 
+use crate::checkpoints::ConfigChange;
 use crate::tier_types::{
-    GuildMasterRewardTier, GuildMasterRewardTierMultiValue, RewardTier, UserRewardTier,
-    UserRewardTierMultiValue,
+    GuildMasterRewardTier, GuildMasterRewardTierMultiValue, RewardCurve, RewardTier,
+    UserRewardTier, UserRewardTierMultiValue,
 };
 
 multiversx_sc::imports!();
@@ -16,7 +17,9 @@ pub const MAX_TIERS: usize = 5;
 pub const FIRST_INDEX_VEC_MAPPER: usize = 1;
 
 #[multiversx_sc::module]
-pub trait TierModule: crate::global_config::GlobalConfigModule {
+pub trait TierModule:
+    crate::global_config::GlobalConfigModule + crate::checkpoints::CheckpointsModule
+{
     #[only_owner]
     #[endpoint(setMaxStakedTokens)]
     fn set_max_staked_tokens(&self, max_staked_tokens: BigUint) {
@@ -42,10 +45,13 @@ pub trait TierModule: crate::global_config::GlobalConfigModule {
         &self,
         tiers: MultiValueEncoded<GuildMasterRewardTierMultiValue<Self::Api>>,
     ) {
+        self.apply_scheduled_tiers();
+
         let mut tiers_mapper = self.guild_master_tiers();
         self.require_empty_mapper(&tiers_mapper);
 
         self.set_guild_master_tiers_common(&mut tiers_mapper, tiers);
+        self.add_guild_master_tiers_checkpoint(self.blockchain().get_block_timestamp());
     }
 
     /// Pairs of (max_stake, apr)
@@ -57,21 +63,27 @@ pub trait TierModule: crate::global_config::GlobalConfigModule {
         &self,
         tiers: MultiValueEncoded<GuildMasterRewardTierMultiValue<Self::Api>>,
     ) {
+        self.apply_scheduled_tiers();
+
         let mut tiers_mapper = self.guild_master_tiers();
         tiers_mapper.clear();
 
         self.set_guild_master_tiers_common(&mut tiers_mapper, tiers);
+        self.add_guild_master_tiers_checkpoint(self.blockchain().get_block_timestamp());
     }
 
     #[only_owner]
     #[endpoint(setGuildMasterTierApr)]
     fn set_guild_master_tier_apr(&self, max_stake: BigUint, new_apr: Percent) {
+        self.apply_scheduled_tiers();
+
         let mut tiers_mapper = self.guild_master_tiers();
         let reward_tier = GuildMasterRewardTier {
             max_stake,
             apr: new_apr,
         };
         self.set_apr(&mut tiers_mapper, reward_tier);
+        self.add_guild_master_tiers_checkpoint(self.blockchain().get_block_timestamp());
     }
 
     /// Pairs of (max_percentage_staked, apr)
@@ -80,10 +92,13 @@ pub trait TierModule: crate::global_config::GlobalConfigModule {
     #[only_owner]
     #[endpoint(addUserTiers)]
     fn add_user_tiers(&self, tiers: MultiValueEncoded<UserRewardTierMultiValue>) {
+        self.apply_scheduled_tiers();
+
         let mut tiers_mapper = self.user_tiers();
         self.require_empty_mapper(&tiers_mapper);
 
         self.set_user_tiers_common(&mut tiers_mapper, tiers);
+        self.add_user_tiers_checkpoint(self.blockchain().get_block_timestamp());
     }
 
     /// Pairs of (max_percentage_staked, apr)
@@ -92,21 +107,53 @@ pub trait TierModule: crate::global_config::GlobalConfigModule {
     #[only_owner]
     #[endpoint(updateUserTiers)]
     fn update_user_tiers(&self, tiers: MultiValueEncoded<UserRewardTierMultiValue>) {
+        self.apply_scheduled_tiers();
+
         let mut tiers_mapper = self.user_tiers();
         tiers_mapper.clear();
 
         self.set_user_tiers_common(&mut tiers_mapper, tiers);
+        self.add_user_tiers_checkpoint(self.blockchain().get_block_timestamp());
     }
 
     #[only_owner]
     #[endpoint(setUserTierApr)]
     fn set_user_tier_apr(&self, max_percentage_staked: Percent, new_apr: Percent) {
+        self.apply_scheduled_tiers();
+
         let mut tiers_mapper = self.user_tiers();
         let reward_tier = UserRewardTier {
             max_percentage_staked,
             apr: new_apr,
         };
         self.set_apr(&mut tiers_mapper, reward_tier);
+        self.add_user_tiers_checkpoint(self.blockchain().get_block_timestamp());
+    }
+
+    /// Step uses the APR of the first matching tier.
+    /// PiecewiseLinear interpolates the APR between the tiers' max values.
+    /// InverseStakedRatio ignores the tiers.
+    /// For guild masters, the staked ratio is the guild's stake out of max_staked_tokens
+    #[only_owner]
+    #[endpoint(setGuildMasterRewardCurve)]
+    fn set_guild_master_reward_curve(&self, curve: RewardCurve) {
+        self.apply_scheduled_tiers();
+
+        self.require_valid_curve(&curve);
+
+        self.guild_master_reward_curve().set(&curve);
+        self.add_config_checkpoint(ConfigChange::GuildMasterRewardCurve(curve));
+    }
+
+    #[only_owner]
+    #[endpoint(setUserRewardCurve)]
+    fn set_user_reward_curve(&self, curve: RewardCurve) {
+        self.apply_scheduled_tiers();
+
+        self.require_valid_curve(&curve);
+
+        self.user_reward_curve().set(&curve);
+        self.add_config_checkpoint(ConfigChange::UserRewardCurve(curve));
     }
 
     fn set_guild_master_tiers_common(
@@ -219,6 +266,12 @@ pub trait TierModule: crate::global_config::GlobalConfigModule {
         );
     }
 
+    fn require_valid_curve(&self, curve: &RewardCurve) {
+        if let RewardCurve::InverseStakedRatio(params) = curve {
+            require!(params.is_valid(), "Invalid curve params");
+        }
+    }
+
     fn require_empty_mapper<T: TopEncode + TopDecode + RewardTier<Self::Api>>(
         &self,
         mapper: &VecMapper<T>,
@@ -229,11 +282,11 @@ pub trait TierModule: crate::global_config::GlobalConfigModule {
         );
     }
 
-    #[view(getGuildMasterTiers)]
-    #[storage_mapper("guildMasterTiers")]
-    fn guild_master_tiers(&self) -> VecMapper<GuildMasterRewardTier<Self::Api>>;
+    #[view(getGuildMasterRewardCurve)]
+    #[storage_mapper("guildMasterRewardCurve")]
+    fn guild_master_reward_curve(&self) -> SingleValueMapper<RewardCurve>;
 
-    #[view(getUserTiers)]
-    #[storage_mapper("userTiers")]
-    fn user_tiers(&self) -> VecMapper<UserRewardTier>;
+    #[view(getUserRewardCurve)]
+    #[storage_mapper("userRewardCurve")]
+    fn user_reward_curve(&self) -> SingleValueMapper<RewardCurve>;
 }
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           32
+// Endpoints:                           66
 // Async Callback (empty):               1
-// Total number of exported functions:  35
+// Total number of exported functions:  69
 
 #![no_std]
 
@@ -27,20 +27,29 @@ multiversx_sc_wasm_adapter::endpoints! {
         addUserTiers => add_user_tiers
         updateUserTiers => update_user_tiers
         setUserTierApr => set_user_tier_apr
-        getGuildMasterTiers => guild_master_tiers
-        getUserTiers => user_tiers
+        setGuildMasterRewardCurve => set_guild_master_reward_curve
+        setUserRewardCurve => set_user_reward_curve
+        getGuildMasterRewardCurve => guild_master_reward_curve
+        getUserRewardCurve => user_reward_curve
         setMinStakeUser => set_min_stake_user
         setMinStakeGuildMaster => set_min_stake_guild_master
         setTotalStakingTokenMinted => set_total_staking_token_minted
         increaseStakedTokens => increase_staked_tokens
         decreaseStakedTokens => decrease_staked_tokens
-        setSecondsPerBlock => set_seconds_per_block
-        setPerBlockRewardAmount => set_per_block_reward_amount
+        setPerSecondRewardAmount => set_per_second_reward_amount
+        setBoostedYieldsFactors => set_boosted_yields_factors
+        setEnergyFactoryAddress => set_energy_factory_address
         pauseAllGuilds => pause_all_guilds
         unpauseAllGuilds => unpause_all_guilds
+        setMaxGuildMasterCommission => set_max_guild_master_commission
+        setCommissionNoticeSeconds => set_commission_notice_seconds
+        clearLegacyBlockEmission => clear_legacy_block_emission
+        setInstantUnbondFee => set_instant_unbond_fee
+        disableInstantUnbond => disable_instant_unbond
         getMaxStakedTokens => max_staked_tokens
         getMinUnbondEpochsUser => min_unbond_epochs_user
         getMinUnbondEpochsGuildMaster => min_unbond_epochs_guild_master
+        getInstantUnbondFee => instant_unbond_fee
         getMinStakeUser => min_stake_user
         getMinStakeGuildMaster => min_stake_guild_master
         getTotalStakingTokenMinted => total_staking_token_minted
@@ -49,9 +58,34 @@ multiversx_sc_wasm_adapter::endpoints! {
         getBaseUnbondTokenId => base_unbond_token_id
         getBaseTokenDisplayName => base_token_display_name
         getTokenDecimals => tokens_decimals
-        getSecondsPerBlock => seconds_per_block
-        getPerBlockRewardAmount => per_block_reward_amount
+        getPerSecondRewardAmount => per_second_reward_amount
+        getBoostedYieldsFactors => boosted_yields_factors
+        getEnergyFactoryAddress => energy_factory_address
         areAllGuildsPaused => global_pause_status
+        getMaxGuildMasterCommission => max_guild_master_commission
+        getCommissionNoticeSeconds => commission_notice_seconds
+        getSecondsPerBlock => legacy_seconds_per_block
+        getPerBlockRewardAmount => legacy_per_block_reward_amount
+        applyScheduledTiers => apply_scheduled_tiers
+        getConfigCheckpoints => config_checkpoints
+        getGuildMasterTiers => guild_master_tiers
+        getUserTiers => user_tiers
+        getScheduledGuildMasterTiersTimestamp => scheduled_guild_master_tiers_timestamp
+        getScheduledGuildMasterTiers => scheduled_guild_master_tiers
+        getScheduledUserTiersTimestamp => scheduled_user_tiers_timestamp
+        getScheduledUserTiers => scheduled_user_tiers
+        scheduleGuildMasterTiersUpdate => schedule_guild_master_tiers_update
+        scheduleUserTiersUpdate => schedule_user_tiers_update
+        cancelScheduledGuildMasterTiersUpdate => cancel_scheduled_guild_master_tiers_update
+        cancelScheduledUserTiersUpdate => cancel_scheduled_user_tiers_update
+        addLockOptions => add_lock_options
+        removeLockOptions => remove_lock_options
+        getLockOptions => get_lock_options
+        setEmissionPhases => set_emission_phases
+        setEmissionDecay => set_emission_decay
+        clearEmissionSchedule => clear_emission_schedule
+        getUpcomingEmissions => get_upcoming_emissions
+        getEmissionSchedule => emission_schedule
     )
 }
 
@@ -0,0 +1,278 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use common_structs::{Epoch, Percent};
+use guild_sc_config::global_config::BoostedYieldsFactors;
+
+use crate::custom_rewards::MAX_PERCENT;
+
+pub type Week = usize;
+
+pub const EPOCHS_IN_WEEK: Epoch = 7;
+pub const FIRST_WEEK: Week = 1;
+pub const USER_MAX_CLAIM_WEEKS: Week = 4;
+
+mod energy_factory_proxy {
+    multiversx_sc::imports!();
+
+    #[multiversx_sc::proxy]
+    pub trait EnergyFactoryProxy {
+        #[view(getEnergyAmountForUser)]
+        fn get_energy_amount_for_user(&self, user: ManagedAddress) -> BigUint;
+    }
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct BoostedYieldsClaimProgress<M: ManagedTypeApi> {
+    pub week: Week,
+    pub energy: BigUint<M>,
+    pub farm_amount: BigUint<M>,
+}
+
+#[multiversx_sc::module]
+pub trait BoostedYieldsModule:
+    crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+{
+    #[view(getCurrentWeek)]
+    fn get_current_week(&self) -> Week {
+        let current_epoch = self.blockchain().get_block_epoch();
+        let first_week_start_epoch = self.first_week_start_epoch().get();
+        if current_epoch < first_week_start_epoch {
+            return FIRST_WEEK;
+        }
+
+        let weeks_passed = (current_epoch - first_week_start_epoch) / EPOCHS_IN_WEEK;
+
+        weeks_passed as Week + FIRST_WEEK
+    }
+
+    /// Moves the guild's boosted percentage of the users' rewards into the current week's pool
+    fn collect_boosted_yields_rewards(&self, user_rewards: &mut BigUint) {
+        let boosted_rewards = self.calculate_boosted_yields_cut(user_rewards);
+        if boosted_rewards == 0 {
+            return;
+        }
+
+        let current_week = self.advance_boosted_yields_week();
+        self.boosted_rewards_for_week(current_week)
+            .update(|total| *total += &boosted_rewards);
+
+        *user_rewards -= boosted_rewards;
+    }
+
+    fn calculate_boosted_yields_cut(&self, user_rewards: &BigUint) -> BigUint {
+        let percentage = self.boosted_yields_rewards_percentage().get();
+        if percentage == 0 {
+            return BigUint::zero();
+        }
+
+        user_rewards * percentage / MAX_PERCENT
+    }
+
+    fn advance_boosted_yields_week(&self) -> Week {
+        let current_week = self.get_current_week();
+        let last_update_week_mapper = self.last_boosted_yields_update_week();
+        let last_update_week = last_update_week_mapper.get();
+        if last_update_week >= current_week {
+            return current_week;
+        }
+
+        // user contributions carry over until the user's next interaction
+        if last_update_week >= FIRST_WEEK {
+            let total_energy = self.total_energy_for_week(last_update_week).get();
+            let total_farm_amount = self.total_farm_amount_for_week(last_update_week).get();
+            self.total_energy_for_week(current_week).set(total_energy);
+            self.total_farm_amount_for_week(current_week)
+                .set(total_farm_amount);
+        }
+
+        last_update_week_mapper.set(current_week);
+
+        current_week
+    }
+
+    /// Saves the rewards for the user's completed weeks and refreshes their energy snapshot
+    fn update_boosted_yields_for_user(&self, user: &ManagedAddress) {
+        let guild_master = self.guild_master_address().get();
+        if user == &guild_master {
+            return;
+        }
+
+        let current_week = self.advance_boosted_yields_week();
+        let progress_mapper = self.user_boosted_yields_progress(user);
+        if !progress_mapper.is_empty() {
+            let progress = progress_mapper.get();
+            let rewards = self.calculate_boosted_rewards_until_week(&progress, current_week);
+            if rewards > 0 {
+                self.user_unclaimed_boosted_rewards(user)
+                    .update(|unclaimed| *unclaimed += rewards);
+            }
+
+            self.total_energy_for_week(current_week)
+                .update(|total| *total -= &progress.energy);
+            self.total_farm_amount_for_week(current_week)
+                .update(|total| *total -= &progress.farm_amount);
+        }
+
+        let (energy, farm_amount) = self.get_user_boosted_yields_contribution(user);
+        if energy == 0 && farm_amount == 0 {
+            progress_mapper.clear();
+
+            return;
+        }
+
+        self.total_energy_for_week(current_week)
+            .update(|total| *total += &energy);
+        self.total_farm_amount_for_week(current_week)
+            .update(|total| *total += &farm_amount);
+
+        progress_mapper.set(BoostedYieldsClaimProgress {
+            week: current_week,
+            energy,
+            farm_amount,
+        });
+    }
+
+    fn claim_boosted_yields_rewards(&self, user: &ManagedAddress) -> BigUint {
+        self.update_boosted_yields_for_user(user);
+
+        let unclaimed_mapper = self.user_unclaimed_boosted_rewards(user);
+        let rewards = unclaimed_mapper.get();
+        unclaimed_mapper.clear();
+
+        rewards
+    }
+
+    fn get_pending_boosted_yields_rewards(&self, user: &ManagedAddress) -> BigUint {
+        let mut rewards = self.user_unclaimed_boosted_rewards(user).get();
+        let guild_master = self.guild_master_address().get();
+        let progress_mapper = self.user_boosted_yields_progress(user);
+        if user == &guild_master || progress_mapper.is_empty() {
+            return rewards;
+        }
+
+        let current_week = self.get_current_week();
+        rewards += self.calculate_boosted_rewards_until_week(&progress_mapper.get(), current_week);
+
+        rewards
+    }
+
+    fn calculate_boosted_rewards_until_week(
+        &self,
+        progress: &BoostedYieldsClaimProgress<Self::Api>,
+        end_week: Week,
+    ) -> BigUint {
+        let mut total_rewards = BigUint::zero();
+        let opt_factors = self.get_boosted_yields_factors();
+        if opt_factors.is_none() || progress.week >= end_week {
+            return total_rewards;
+        }
+
+        let factors = unsafe { opt_factors.unwrap_unchecked() };
+        let start_week =
+            core::cmp::max(progress.week, end_week.saturating_sub(USER_MAX_CLAIM_WEEKS));
+        for week in start_week..end_week {
+            total_rewards += self.calculate_boosted_rewards_for_week(week, progress, &factors);
+        }
+
+        total_rewards
+    }
+
+    fn calculate_boosted_rewards_for_week(
+        &self,
+        week: Week,
+        progress: &BoostedYieldsClaimProgress<Self::Api>,
+        factors: &BoostedYieldsFactors<Self::Api>,
+    ) -> BigUint {
+        let weekly_rewards = self.boosted_rewards_for_week(week).get();
+        let total_farm_amount = self.total_farm_amount_for_week(week).get();
+        if weekly_rewards == 0 || total_farm_amount == 0 {
+            return BigUint::zero();
+        }
+
+        let max_rewards = &factors.max_rewards_factor * &weekly_rewards * &progress.farm_amount
+            / &total_farm_amount;
+
+        let total_energy = self.total_energy_for_week(week).get();
+        let rewards_by_energy = if total_energy > 0 {
+            &weekly_rewards * &factors.user_rewards_energy_const * &progress.energy / &total_energy
+        } else {
+            BigUint::zero()
+        };
+        let rewards_by_farm_amount =
+            &weekly_rewards * &factors.user_rewards_farm_const * &progress.farm_amount
+                / &total_farm_amount;
+
+        let constants_base = &factors.user_rewards_energy_const + &factors.user_rewards_farm_const;
+        let boosted_rewards = (rewards_by_energy + rewards_by_farm_amount) / constants_base;
+
+        core::cmp::min(max_rewards, boosted_rewards)
+    }
+
+    fn get_user_boosted_yields_contribution(&self, user: &ManagedAddress) -> (BigUint, BigUint) {
+        let opt_factors = self.get_boosted_yields_factors();
+        if opt_factors.is_none() {
+            return (BigUint::zero(), BigUint::zero());
+        }
+
+        let factors = unsafe { opt_factors.unwrap_unchecked() };
+        let farm_amount = self.user_tokens(user).get();
+        let energy = self.get_user_energy(user);
+        if energy < factors.min_energy_amount || farm_amount < factors.min_farm_amount {
+            return (BigUint::zero(), BigUint::zero());
+        }
+
+        (energy, farm_amount)
+    }
+
+    fn get_user_energy(&self, user: &ManagedAddress) -> BigUint {
+        match self.get_energy_factory_address() {
+            Some(energy_factory) => self
+                .energy_factory_proxy(energy_factory)
+                .get_energy_amount_for_user(user.clone())
+                .execute_on_dest_context(),
+            None => BigUint::zero(),
+        }
+    }
+
+    #[proxy]
+    fn energy_factory_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> energy_factory_proxy::Proxy<Self::Api>;
+
+    #[view(getBoostedYieldsRewardsPercentage)]
+    #[storage_mapper("boostedYieldsRewardsPercentage")]
+    fn boosted_yields_rewards_percentage(&self) -> SingleValueMapper<Percent>;
+
+    #[view(getFirstWeekStartEpoch)]
+    #[storage_mapper("firstWeekStartEpoch")]
+    fn first_week_start_epoch(&self) -> SingleValueMapper<Epoch>;
+
+    #[storage_mapper("lastBoostedYieldsUpdateWeek")]
+    fn last_boosted_yields_update_week(&self) -> SingleValueMapper<Week>;
+
+    #[view(getBoostedRewardsForWeek)]
+    #[storage_mapper("boostedRewardsForWeek")]
+    fn boosted_rewards_for_week(&self, week: Week) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalEnergyForWeek)]
+    #[storage_mapper("totalEnergyForWeek")]
+    fn total_energy_for_week(&self, week: Week) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalFarmAmountForWeek)]
+    #[storage_mapper("totalFarmAmountForWeek")]
+    fn total_farm_amount_for_week(&self, week: Week) -> SingleValueMapper<BigUint>;
+
+    #[view(getUserBoostedYieldsProgress)]
+    #[storage_mapper("userBoostedYieldsProgress")]
+    fn user_boosted_yields_progress(
+        &self,
+        user: &ManagedAddress,
+    ) -> SingleValueMapper<BoostedYieldsClaimProgress<Self::Api>>;
+
+    #[view(getUserUnclaimedBoostedRewards)]
+    #[storage_mapper("userUnclaimedBoostedRewards")]
+    fn user_unclaimed_boosted_rewards(&self, user: &ManagedAddress) -> SingleValueMapper<BigUint>;
+}
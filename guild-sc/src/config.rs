@@ -2,6 +2,7 @@ multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
 use common_structs::Nonce;
+use guild_sc_config::checkpoints::Timestamp;
 use pausable::State;
 
 #[multiversx_sc::module]
@@ -12,6 +13,14 @@ pub trait ConfigModule: pausable::PausableModule + permissions_module::Permissio
         state == State::Active
     }
 
+    /// Rewards can only be compounded when they're paid in the farming token
+    fn require_compounding_enabled(&self) {
+        require!(
+            self.farming_token_id().get() == self.reward_token_id().get(),
+            "Compounding not available"
+        );
+    }
+
     #[view(getFarmingTokenId)]
     #[storage_mapper("farming_token_id")]
     fn farming_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
@@ -20,18 +29,26 @@ pub trait ConfigModule: pausable::PausableModule + permissions_module::Permissio
     #[storage_mapper("reward_token_id")]
     fn reward_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
 
-    #[view(getPerBlockRewardAmount)]
-    #[storage_mapper("per_block_reward_amount")]
-    fn per_block_reward_amount(&self) -> SingleValueMapper<BigUint>;
+    #[view(getPerSecondRewardAmount)]
+    #[storage_mapper("perSecondRewardAmount")]
+    fn per_second_reward_amount(&self) -> SingleValueMapper<BigUint>;
 
     #[storage_mapper("produce_rewards_enabled")]
     fn produce_rewards_enabled(&self) -> SingleValueMapper<bool>;
 
-    #[view(getLastRewardBlockNonce)]
-    #[storage_mapper("last_reward_block_nonce")]
-    fn last_reward_block_nonce(&self) -> SingleValueMapper<Nonce>;
+    #[view(getLastRewardTimestamp)]
+    #[storage_mapper("lastRewardTimestamp")]
+    fn last_reward_timestamp(&self) -> SingleValueMapper<Timestamp>;
 
     #[view(getDivisionSafetyConstant)]
     #[storage_mapper("division_safety_constant")]
     fn division_safety_constant(&self) -> SingleValueMapper<BigUint>;
+
+    /// Block based accrual, only read when migrating to timestamps
+    #[storage_mapper("last_reward_block_nonce")]
+    fn legacy_last_reward_block_nonce(&self) -> SingleValueMapper<Nonce>;
+
+    /// Block based accrual, only read when migrating to timestamps
+    #[storage_mapper("per_block_reward_amount")]
+    fn legacy_per_block_reward_amount(&self) -> SingleValueMapper<BigUint>;
 }
@@ -6,11 +6,21 @@ use crate::{
     contexts::storage_cache::StorageCache, farm_base_impl::base_traits_impl::FarmStakingWrapper,
 };
 use common_structs::Percent;
-use guild_sc_config::tier_types::{GuildMasterRewardTier, UserRewardTier};
+use guild_sc_config::{
+    checkpoints::{ConfigChange, Timestamp},
+    emission::EmissionSchedule,
+    tier_types::{GuildMasterRewardTier, RewardCurve, UserRewardTier},
+};
 
 pub const MAX_PERCENT: Percent = 10_000;
 pub const SECONDS_IN_YEAR: u64 = 31_536_000;
 
+pub enum ConfigSwitch<M: ManagedTypeApi> {
+    Checkpoint(ConfigChange<M>),
+    ScheduledTiers { guild_master: bool, user: bool },
+    GuildMasterCommission,
+}
+
 mod guild_factory_proxy {
     multiversx_sc::imports!();
 
@@ -18,6 +28,10 @@ mod guild_factory_proxy {
     pub trait GuildFactoryProxy {
         #[endpoint(requestRewards)]
         fn request_rewards(&self, amount: BigUint) -> BigUint;
+
+        #[payable("*")]
+        #[endpoint(stakeRewardsInGuild)]
+        fn stake_rewards_in_guild(&self, guild: ManagedAddress, original_caller: ManagedAddress);
     }
 }
 
@@ -35,6 +49,9 @@ pub trait CustomRewardsModule:
     + crate::tiered_rewards::read_config::ReadConfigModule
     + crate::tiered_rewards::total_tokens::TokenPerTierModule
     + crate::user_actions::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + crate::guild_master_commission::GuildMasterCommissionModule
+    + crate::locked_positions::LockedPositionsModule
 {
     #[payable("*")]
     #[endpoint(topUpRewards)]
@@ -54,13 +71,102 @@ pub trait CustomRewardsModule:
         self.reward_capacity().update(|r| *r += payment_amount);
     }
 
+    #[endpoint(setBoostedYieldsRewardsPercentage)]
+    fn set_boosted_yields_rewards_percentage(&self, percentage: Percent) {
+        self.require_not_closing();
+
+        let guild_master = self.guild_master_address().get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == guild_master,
+            "Only guild master may set boosted yields percentage"
+        );
+        require!(percentage <= MAX_PERCENT, "Invalid percentage");
+
+        let mut storage_cache = StorageCache::new(self);
+        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+
+        self.boosted_yields_rewards_percentage().set(percentage);
+    }
+
+    /// Percentage of the users' rewards paid to the guild master
+    #[endpoint(setGuildMasterCommission)]
+    fn set_guild_master_commission_endpoint(&self, percentage: Percent) {
+        self.require_not_closing();
+
+        let guild_master = self.guild_master_address().get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == guild_master,
+            "Only guild master may set commission"
+        );
+
+        let mut storage_cache = StorageCache::new(self);
+        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+
+        self.set_guild_master_commission(percentage);
+    }
+
+    #[endpoint(claimGuildMasterCommission)]
+    fn claim_guild_master_commission(&self) -> EsdtTokenPayment {
+        self.require_not_globally_paused();
+
+        let guild_master = self.guild_master_address().get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == guild_master,
+            "Only guild master may claim commission"
+        );
+
+        let mut storage_cache = StorageCache::new(self);
+        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+
+        self.pay_guild_master_commission(&caller, &mut storage_cache)
+    }
+
+    fn pay_guild_master_commission(
+        &self,
+        guild_master: &ManagedAddress,
+        storage_cache: &mut StorageCache<Self>,
+    ) -> EsdtTokenPayment {
+        let accumulated_commission = self.accumulated_guild_master_commission().take();
+        let commission = self.take_payable_rewards(
+            guild_master,
+            accumulated_commission,
+            &mut storage_cache.reward_reserve,
+        );
+
+        let payment = EsdtTokenPayment::new(storage_cache.reward_token_id.clone(), 0, commission);
+        self.send_payment_non_zero(guild_master, &payment);
+
+        payment
+    }
+
+    /// Pays the rewards held back while the guild was unfunded, e.g. after exiting the guild
+    #[endpoint(claimHeldBackRewards)]
+    fn claim_held_back_rewards(&self) -> EsdtTokenPayment {
+        self.require_not_globally_paused();
+
+        let mut storage_cache = StorageCache::new(self);
+        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+
+        let caller = self.blockchain().get_caller();
+        let rewards =
+            self.take_payable_rewards(&caller, BigUint::zero(), &mut storage_cache.reward_reserve);
+
+        let payment = EsdtTokenPayment::new(storage_cache.reward_token_id.clone(), 0, rewards);
+        self.send_payment_non_zero(&caller, &payment);
+
+        payment
+    }
+
     #[only_owner]
     #[endpoint(startProduceRewards)]
     fn start_produce_rewards_endpoint(&self) {
         self.start_produce_rewards();
     }
 
-    fn get_amount_apr_bounded(&self) -> TotalRewards<Self::Api> {
+    fn get_amount_apr_bounded(&self, elapsed_seconds: u64) -> TotalRewards<Self::Api> {
         let mut total_guild_master = BigUint::zero();
         let mut total_users = BigUint::zero();
 
@@ -69,7 +175,7 @@ pub trait CustomRewardsModule:
             let guild_master_apr = self.find_guild_master_tier_apr(&total_user_base_tokens);
             let guild_master_tokens = self.guild_master_tokens().get();
             let amount_bounded_guild_master =
-                self.bound_amount_by_apr(&guild_master_tokens, guild_master_apr);
+                self.bound_amount_by_apr(&guild_master_tokens, guild_master_apr, elapsed_seconds);
             total_guild_master += amount_bounded_guild_master;
 
             guild_master_tokens
@@ -80,7 +186,8 @@ pub trait CustomRewardsModule:
         let total_user_tokens = self.farm_token_supply().get() - guild_master_tokens_total;
         let staked_percent = self.get_total_staked_percent();
         let user_apr = self.find_user_tier_apr(staked_percent);
-        let amount_bounded = self.bound_amount_by_apr(&total_user_tokens, user_apr);
+        let amount_bounded =
+            self.bound_amount_by_apr(&total_user_tokens, user_apr, elapsed_seconds);
         total_users += amount_bounded;
 
         TotalRewards {
@@ -92,24 +199,48 @@ pub trait CustomRewardsModule:
     // percentage_staked unused
     fn find_guild_master_tier_apr(&self, total_farming_tokens: &BigUint) -> Percent {
         let mapper = self.internal_guild_master_tiers();
-        let tier = self.find_tier_common(total_farming_tokens, Percent::default(), &mapper);
-
-        tier.apr
+        match self.internal_guild_master_reward_curve().get() {
+            RewardCurve::Step => {
+                let tier = self.find_tier_common(total_farming_tokens, Percent::default(), &mapper);
+
+                tier.apr
+            }
+            RewardCurve::PiecewiseLinear => {
+                self.interpolate_tier_apr(total_farming_tokens, &mapper)
+            }
+            RewardCurve::InverseStakedRatio(params) => {
+                let max_staked_tokens = self.get_max_staked_tokens();
+                if max_staked_tokens == 0 {
+                    return params.min_apr;
+                }
+
+                let staked_ratio = (total_farming_tokens * MAX_PERCENT / max_staked_tokens)
+                    .to_u64()
+                    .unwrap_or(MAX_PERCENT);
+
+                params.get_apr(staked_ratio)
+            }
+        }
     }
 
     // total_farming_tokens unused
     fn find_user_tier_apr(&self, percentage_staked: Percent) -> Percent {
         let mapper = self.internal_user_tiers();
-        let tier = self.find_tier_common(&BigUint::default(), percentage_staked, &mapper);
-
-        tier.apr
+        match self.internal_user_reward_curve().get() {
+            RewardCurve::Step => {
+                let tier = self.find_tier_common(&BigUint::default(), percentage_staked, &mapper);
+
+                tier.apr
+            }
+            RewardCurve::PiecewiseLinear => {
+                self.interpolate_tier_apr(&BigUint::from(percentage_staked), &mapper)
+            }
+            RewardCurve::InverseStakedRatio(params) => params.get_apr(percentage_staked),
+        }
     }
 
-    fn bound_amount_by_apr(&self, amount: &BigUint, apr: Percent) -> BigUint {
-        let seconds_per_block = self.internal_seconds_per_block().get();
-        let blocks_in_year = SECONDS_IN_YEAR / seconds_per_block;
-
-        amount * apr / MAX_PERCENT / blocks_in_year
+    fn bound_amount_by_apr(&self, amount: &BigUint, apr: Percent, elapsed_seconds: u64) -> BigUint {
+        amount * apr * elapsed_seconds / MAX_PERCENT / SECONDS_IN_YEAR
     }
 
     fn get_total_staked_percent(&self) -> u64 {
@@ -135,30 +266,176 @@ pub trait CustomRewardsModule:
         received_rewards
     }
 
-    fn update_internal_seconds_per_block(&self) {
-        let seconds_per_block = self.get_seconds_per_block();
-        self.internal_seconds_per_block().set(seconds_per_block);
-    }
-
-    fn update_per_block_reward_amount(&self) {
-        let per_block_reward_amount = self.get_per_block_reward_amount();
-        self.per_block_reward_amount().set(per_block_reward_amount);
+    fn update_per_second_reward_amount(&self) {
+        let per_second_reward_amount = self.get_per_second_reward_amount();
+        self.per_second_reward_amount()
+            .set(per_second_reward_amount);
+        self.internal_emission_schedule()
+            .set(self.get_emission_schedule());
     }
 
     fn update_internal_tiers(&self) {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+
         let mut internal_guild_master_tiers_mapper = self.internal_guild_master_tiers();
+        match self.get_scheduled_guild_master_tiers_timestamp() {
+            Some(timestamp) if timestamp <= current_timestamp => self.copy_tiers(
+                &mut internal_guild_master_tiers_mapper,
+                &self.get_scheduled_guild_master_tiers_mapper(),
+            ),
+            _ => self.copy_tiers(
+                &mut internal_guild_master_tiers_mapper,
+                &self.get_guild_master_tiers_mapper(),
+            ),
+        }
+
         let mut internal_user_tiers_mapper = self.internal_user_tiers();
-        internal_guild_master_tiers_mapper.clear();
-        internal_user_tiers_mapper.clear();
+        match self.get_scheduled_user_tiers_timestamp() {
+            Some(timestamp) if timestamp <= current_timestamp => self.copy_tiers(
+                &mut internal_user_tiers_mapper,
+                &self.get_scheduled_user_tiers_mapper(),
+            ),
+            _ => self.copy_tiers(
+                &mut internal_user_tiers_mapper,
+                &self.get_user_tiers_mapper(),
+            ),
+        }
 
-        let external_guild_master_tiers_mapper = self.get_guild_master_tiers_mapper();
-        for tier in external_guild_master_tiers_mapper.iter() {
-            internal_guild_master_tiers_mapper.push(&tier);
+        self.internal_guild_master_reward_curve()
+            .set(self.get_guild_master_reward_curve());
+        self.internal_user_reward_curve()
+            .set(self.get_user_reward_curve());
+    }
+
+    /// Next change of the accrual parameters in (after_timestamp, until_timestamp].
+    /// Pending scheduled tiers always come after the recorded checkpoints.
+    fn get_next_config_switch(
+        &self,
+        after_timestamp: Timestamp,
+        until_timestamp: Timestamp,
+    ) -> Option<(Timestamp, ConfigSwitch<Self::Api>)> {
+        let is_in_range =
+            |timestamp: &Timestamp| *timestamp > after_timestamp && *timestamp <= until_timestamp;
+        let opt_commission_timestamp = self
+            .get_pending_guild_master_commission_timestamp()
+            .filter(is_in_range);
+
+        let checkpoint_index = self.last_config_checkpoint().get() + 1;
+        let checkpoints_mapper = self.get_config_checkpoints_mapper();
+        if checkpoint_index <= checkpoints_mapper.len() {
+            let checkpoint = checkpoints_mapper.get(checkpoint_index);
+            if checkpoint.timestamp <= until_timestamp {
+                return match opt_commission_timestamp {
+                    Some(timestamp) if timestamp < checkpoint.timestamp => {
+                        Some((timestamp, ConfigSwitch::GuildMasterCommission))
+                    }
+                    _ => Some((
+                        checkpoint.timestamp,
+                        ConfigSwitch::Checkpoint(checkpoint.change),
+                    )),
+                };
+            }
         }
 
-        let external_user_tiers_mapper = self.get_user_tiers_mapper();
-        for tier in external_user_tiers_mapper.iter() {
-            internal_user_tiers_mapper.push(&tier);
+        let opt_guild_master_timestamp = self
+            .get_scheduled_guild_master_tiers_timestamp()
+            .filter(is_in_range);
+        let opt_user_timestamp = self
+            .get_scheduled_user_tiers_timestamp()
+            .filter(is_in_range);
+        let opt_tiers_timestamp = [opt_guild_master_timestamp, opt_user_timestamp]
+            .into_iter()
+            .flatten()
+            .min();
+        if let Some(commission_timestamp) = opt_commission_timestamp {
+            let is_before_tiers = opt_tiers_timestamp.map_or(true, |tiers_timestamp| {
+                commission_timestamp < tiers_timestamp
+            });
+            if is_before_tiers {
+                return Some((commission_timestamp, ConfigSwitch::GuildMasterCommission));
+            }
+        }
+
+        let timestamp = opt_tiers_timestamp?;
+
+        Some((
+            timestamp,
+            ConfigSwitch::ScheduledTiers {
+                guild_master: opt_guild_master_timestamp == Some(timestamp),
+                user: opt_user_timestamp == Some(timestamp),
+            },
+        ))
+    }
+
+    fn apply_config_switch(&self, switch: ConfigSwitch<Self::Api>) {
+        match switch {
+            ConfigSwitch::Checkpoint(change) => {
+                self.last_config_checkpoint().update(|index| *index += 1);
+                self.apply_config_change(change);
+            }
+            ConfigSwitch::ScheduledTiers { guild_master, user } => {
+                if guild_master {
+                    self.copy_tiers(
+                        &mut self.internal_guild_master_tiers(),
+                        &self.get_scheduled_guild_master_tiers_mapper(),
+                    );
+                }
+                if user {
+                    self.copy_tiers(
+                        &mut self.internal_user_tiers(),
+                        &self.get_scheduled_user_tiers_mapper(),
+                    );
+                }
+            }
+            ConfigSwitch::GuildMasterCommission => {
+                self.apply_pending_guild_master_commission();
+            }
+        }
+    }
+
+    fn apply_config_change(&self, change: ConfigChange<Self::Api>) {
+        match change {
+            ConfigChange::PerSecondRewardAmount(per_second_reward_amount) => {
+                self.per_second_reward_amount()
+                    .set(per_second_reward_amount);
+            }
+            ConfigChange::TotalStakingTokenMinted(minted) => {
+                self.internal_total_staking_token_minted().set(minted);
+            }
+            ConfigChange::GuildMasterTiers(tiers) => {
+                let mut mapper = self.internal_guild_master_tiers();
+                mapper.clear();
+                for tier in &tiers {
+                    mapper.push(&tier);
+                }
+            }
+            ConfigChange::UserTiers(tiers) => {
+                let mut mapper = self.internal_user_tiers();
+                mapper.clear();
+                for tier in &tiers {
+                    mapper.push(&tier);
+                }
+            }
+            ConfigChange::GuildMasterRewardCurve(curve) => {
+                self.internal_guild_master_reward_curve().set(curve);
+            }
+            ConfigChange::UserRewardCurve(curve) => {
+                self.internal_user_reward_curve().set(curve);
+            }
+            ConfigChange::EmissionSchedule(schedule) => {
+                self.internal_emission_schedule().set(schedule);
+            }
+        }
+    }
+
+    fn copy_tiers<T: TopEncode + TopDecode>(
+        &self,
+        internal_mapper: &mut VecMapper<T>,
+        external_mapper: &VecMapper<T, ManagedAddress>,
+    ) {
+        internal_mapper.clear();
+        for tier in external_mapper.iter() {
+            internal_mapper.push(&tier);
         }
     }
 
@@ -167,11 +444,33 @@ pub trait CustomRewardsModule:
         self.internal_total_staking_token_minted().set(minted);
     }
 
+    /// Guilds that accrued by block nonce continue from the equivalent timestamp,
+    /// so the blocks not yet accrued are not lost.
+    fn migrate_to_timestamp_accrual(&self) {
+        let last_reward_nonce_mapper = self.legacy_last_reward_block_nonce();
+        if last_reward_nonce_mapper.is_empty() {
+            return;
+        }
+
+        let last_reward_nonce = last_reward_nonce_mapper.take();
+        let seconds_per_block = self.legacy_internal_seconds_per_block().take();
+        self.legacy_per_block_reward_amount().clear();
+
+        let current_nonce = self.blockchain().get_block_nonce();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let elapsed_seconds = current_nonce.saturating_sub(last_reward_nonce) * seconds_per_block;
+        self.last_reward_timestamp()
+            .set(current_timestamp.saturating_sub(elapsed_seconds));
+    }
+
     fn update_all(&self) {
-        self.update_internal_seconds_per_block();
-        self.update_per_block_reward_amount();
+        self.update_per_second_reward_amount();
         self.update_internal_tiers();
         self.update_internal_staking_token_minted();
+
+        // the internal values now reflect all the recorded checkpoints
+        let checkpoints_len = self.get_config_checkpoints_mapper().len();
+        self.last_config_checkpoint().set(checkpoints_len);
     }
 
     #[proxy]
@@ -188,15 +487,29 @@ pub trait CustomRewardsModule:
     #[storage_mapper("reward_capacity")]
     fn reward_capacity(&self) -> SingleValueMapper<BigUint>;
 
-    #[storage_mapper("internalSecondsPerBlock")]
-    fn internal_seconds_per_block(&self) -> SingleValueMapper<u64>;
-
     #[storage_mapper("internalGuildMasterTiers")]
     fn internal_guild_master_tiers(&self) -> VecMapper<GuildMasterRewardTier<Self::Api>>;
 
     #[storage_mapper("internalUserTiers")]
     fn internal_user_tiers(&self) -> VecMapper<UserRewardTier>;
 
+    #[storage_mapper("internalGuildMasterRewardCurve")]
+    fn internal_guild_master_reward_curve(&self) -> SingleValueMapper<RewardCurve>;
+
+    #[storage_mapper("internalUserRewardCurve")]
+    fn internal_user_reward_curve(&self) -> SingleValueMapper<RewardCurve>;
+
+    #[storage_mapper("internalEmissionSchedule")]
+    fn internal_emission_schedule(&self) -> SingleValueMapper<EmissionSchedule<Self::Api>>;
+
     #[storage_mapper("internalTotalStakingTokenMinted")]
     fn internal_total_staking_token_minted(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getLastConfigCheckpoint)]
+    #[storage_mapper("lastConfigCheckpoint")]
+    fn last_config_checkpoint(&self) -> SingleValueMapper<usize>;
+
+    /// Block based accrual, only read when migrating to timestamps
+    #[storage_mapper("internalSecondsPerBlock")]
+    fn legacy_internal_seconds_per_block(&self) -> SingleValueMapper<u64>;
 }
@@ -2,15 +2,20 @@ multiversx_sc::imports!();
 
 use core::marker::PhantomData;
 
+use crate::boosted_yields::BoostedYieldsModule;
 use crate::config::ConfigModule;
 use crate::contexts::storage_cache::StorageCache;
 use crate::custom_rewards::CustomRewardsModule;
+use crate::guild_master_commission::GuildMasterCommissionModule;
+use crate::locked_positions::LockedPositionsModule;
 use crate::rewards::RewardsModule;
 use crate::tiered_rewards::total_tokens::TokenPerTierModule;
 use crate::tokens::farm_token::FarmTokenModule;
 use crate::tokens::token_attributes::{LocalFarmToken, StakingFarmTokenAttributes};
 use crate::user_actions::close_guild::CloseGuildModule;
-use common_structs::Nonce;
+use guild_sc_config::checkpoints::Timestamp;
+
+pub const MAX_CONFIG_SWITCHES_PER_CALL: usize = 20;
 
 pub trait FarmStakingTraits:
     crate::custom_rewards::CustomRewardsModule
@@ -23,6 +28,7 @@ pub trait FarmStakingTraits:
     + crate::tiered_rewards::read_config::ReadConfigModule
     + crate::tiered_rewards::total_tokens::TokenPerTierModule
     + crate::user_actions::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
 {
 }
 
@@ -37,6 +43,7 @@ impl<T> FarmStakingTraits for T where
         + crate::tiered_rewards::read_config::ReadConfigModule
         + crate::tiered_rewards::total_tokens::TokenPerTierModule
         + crate::user_actions::close_guild::CloseGuildModule
+        + crate::boosted_yields::BoostedYieldsModule
 {
 }
 
@@ -58,43 +65,155 @@ impl<M: ManagedTypeApi> TotalRewards<M> {
     }
 }
 
+pub struct MintedRewards<M: ManagedTypeApi> {
+    pub split: TotalRewards<M>,
+    pub guild_master_commission: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> MintedRewards<M> {
+    pub fn zero() -> Self {
+        Self {
+            split: TotalRewards::zero(),
+            guild_master_commission: BigUint::zero(),
+        }
+    }
+
+    /// The commission rate in effect at the start of the segment applies to all of it
+    fn add_segment(&mut self, segment_rewards: TotalRewards<M>, commission: BigUint<M>) {
+        self.split.guild_master += segment_rewards.guild_master;
+        self.split.users += segment_rewards.users;
+        self.guild_master_commission += commission;
+    }
+}
+
+pub struct RewardsProjection<M: ManagedTypeApi> {
+    pub unbounded: TotalRewards<M>,
+    pub apr_bounded: TotalRewards<M>,
+}
+
+impl<M: ManagedTypeApi> RewardsProjection<M> {
+    pub fn zero() -> Self {
+        Self {
+            unbounded: TotalRewards::zero(),
+            apr_bounded: TotalRewards::zero(),
+        }
+    }
+
+    pub fn bounded(&self) -> TotalRewards<M> {
+        TotalRewards {
+            guild_master: core::cmp::min(
+                self.unbounded.guild_master.clone(),
+                self.apr_bounded.guild_master.clone(),
+            ),
+            users: core::cmp::min(self.unbounded.users.clone(), self.apr_bounded.users.clone()),
+        }
+    }
+}
+
 pub trait FarmContract {
     type FarmSc: FarmStakingTraits;
 
-    fn calculate_per_block_rewards(
+    fn calculate_per_second_rewards(
         sc: &Self::FarmSc,
-        current_block_nonce: Nonce,
-        last_reward_block_nonce: Nonce,
+        current_timestamp: Timestamp,
+        last_reward_timestamp: Timestamp,
     ) -> BigUint<<Self::FarmSc as ContractBase>::Api> {
-        if current_block_nonce <= last_reward_block_nonce || !sc.produces_per_block_rewards() {
+        if current_timestamp <= last_reward_timestamp || !sc.produces_per_second_rewards() {
             return BigUint::zero();
         }
 
-        let per_block_reward = sc.per_block_reward_amount().get();
-        let block_nonce_diff = current_block_nonce - last_reward_block_nonce;
+        let per_second_reward = sc.per_second_reward_amount().get();
+        sc.internal_emission_schedule().get().amount_between(
+            last_reward_timestamp,
+            current_timestamp,
+            &per_second_reward,
+        )
+    }
 
-        per_block_reward * block_nonce_diff
+    fn mint_per_second_rewards(
+        sc: &Self::FarmSc,
+    ) -> MintedRewards<<Self::FarmSc as ContractBase>::Api> {
+        let current_timestamp = sc.blockchain().get_block_timestamp();
+        let last_reward_timestamp = sc.last_reward_timestamp().get();
+        if current_timestamp <= last_reward_timestamp || sc.guild_closing().get() {
+            return MintedRewards::zero();
+        }
+
+        // config changes apply to the time elapsed after their timestamp
+        let mut minted_rewards = MintedRewards::zero();
+        let mut segment_start = last_reward_timestamp;
+        let mut searched_until = last_reward_timestamp;
+        let mut processed_switches = 0;
+        while let Some((switch_timestamp, switch)) =
+            sc.get_next_config_switch(searched_until, current_timestamp)
+        {
+            // the remaining switches are processed by the next calls
+            if processed_switches == MAX_CONFIG_SWITCHES_PER_CALL {
+                sc.last_reward_timestamp().set(segment_start);
+
+                return minted_rewards;
+            }
+
+            let segment_end = core::cmp::max(switch_timestamp, segment_start);
+            let segment_rewards =
+                Self::calculate_rewards_projection(sc, segment_end, segment_start).bounded();
+            let commission =
+                sc.calculate_guild_master_commission_cut(&segment_rewards.users, segment_start);
+            minted_rewards.add_segment(segment_rewards, commission);
+
+            sc.apply_config_switch(switch);
+            segment_start = segment_end;
+            searched_until = core::cmp::max(switch_timestamp, searched_until);
+            processed_switches += 1;
+        }
+
+        sc.last_reward_timestamp().set(current_timestamp);
+
+        let last_segment_rewards =
+            Self::calculate_rewards_projection(sc, current_timestamp, segment_start).bounded();
+        let commission =
+            sc.calculate_guild_master_commission_cut(&last_segment_rewards.users, segment_start);
+        minted_rewards.add_segment(last_segment_rewards, commission);
+
+        minted_rewards
+    }
+
+    /// The internal config values are only resynced once every config switch was accrued
+    fn update_config_if_synced(sc: &Self::FarmSc) {
+        let current_timestamp = sc.blockchain().get_block_timestamp();
+        if sc.last_reward_timestamp().get() < current_timestamp && !sc.guild_closing().get() {
+            return;
+        }
+
+        sc.update_all();
     }
 
-    fn mint_per_block_rewards(
+    /// Expired locks keep their extra weight for the rewards accrued until now
+    fn process_expired_locks_if_synced(
         sc: &Self::FarmSc,
-    ) -> TotalRewards<<Self::FarmSc as ContractBase>::Api> {
-        let current_block_nonce = sc.blockchain().get_block_nonce();
-        let last_reward_nonce = sc.last_reward_block_nonce().get();
-        if current_block_nonce <= last_reward_nonce || sc.guild_closing().get() {
-            return TotalRewards::zero();
+        user_rps: &BigUint<<Self::FarmSc as ContractBase>::Api>,
+    ) {
+        let current_timestamp = sc.blockchain().get_block_timestamp();
+        if sc.last_reward_timestamp().get() < current_timestamp && !sc.guild_closing().get() {
+            return;
         }
 
-        sc.last_reward_block_nonce().set(current_block_nonce);
+        sc.process_expired_locks(user_rps);
+    }
 
+    fn calculate_rewards_projection(
+        sc: &Self::FarmSc,
+        current_timestamp: Timestamp,
+        last_reward_timestamp: Timestamp,
+    ) -> RewardsProjection<<Self::FarmSc as ContractBase>::Api> {
         let extra_rewards_unbounded =
-            Self::calculate_per_block_rewards(sc, current_block_nonce, last_reward_nonce);
+            Self::calculate_per_second_rewards(sc, current_timestamp, last_reward_timestamp);
         if extra_rewards_unbounded == 0 {
-            return TotalRewards::zero();
+            return RewardsProjection::zero();
         }
 
         if sc.guild_master_tokens().is_empty() {
-            return TotalRewards::zero();
+            return RewardsProjection::zero();
         }
 
         let guild_master_tokens = sc.guild_master_tokens().get();
@@ -107,22 +226,12 @@ pub trait FarmContract {
             users: user_rewards,
         };
 
-        let extra_rewards_apr_bounded_per_block = sc.get_amount_apr_bounded();
-        let block_nonce_diff = current_block_nonce - last_reward_nonce;
-        let extra_rewards_apr_bounded = TotalRewards {
-            guild_master: extra_rewards_apr_bounded_per_block.guild_master * block_nonce_diff,
-            users: extra_rewards_apr_bounded_per_block.users * block_nonce_diff,
-        };
+        let elapsed_seconds = current_timestamp - last_reward_timestamp;
+        let extra_rewards_apr_bounded = sc.get_amount_apr_bounded(elapsed_seconds);
 
-        TotalRewards {
-            guild_master: core::cmp::min(
-                extra_rewards_unbounded_split.guild_master,
-                extra_rewards_apr_bounded.guild_master,
-            ),
-            users: core::cmp::min(
-                extra_rewards_unbounded_split.users,
-                extra_rewards_apr_bounded.users,
-            ),
+        RewardsProjection {
+            unbounded: extra_rewards_unbounded_split,
+            apr_bounded: extra_rewards_apr_bounded,
         }
     }
 
@@ -134,27 +243,34 @@ pub trait FarmContract {
         let mut accumulated_rewards = accumulated_rewards_mapper.get();
         let reward_capacity = sc.reward_capacity().get();
         let mut remaining_rewards = &reward_capacity - &accumulated_rewards;
-        let split_rewards = Self::mint_per_block_rewards(sc);
+        let minted_rewards = Self::mint_per_second_rewards(sc);
+        let split_rewards = minted_rewards.split;
         let total_reward = split_rewards.total();
-        if total_reward > remaining_rewards {
-            let needed_rewards = &total_reward - &remaining_rewards;
-            let received_rewards = sc.request_rewards(needed_rewards);
+        let unfunded_rewards_mapper = sc.unfunded_rewards();
+        let needed_rewards = &total_reward + &unfunded_rewards_mapper.get();
+        if needed_rewards > remaining_rewards {
+            let received_rewards = sc.request_rewards(&needed_rewards - &remaining_rewards);
             remaining_rewards += received_rewards;
         }
 
-        // If needed rewards STILL more than remaining rewards, just return instead of doing additional math
-        if total_reward > remaining_rewards {
-            sc.update_all();
-
-            return;
-        }
+        // the rewards accrue in full, while the unfunded part is held back from the payouts
+        let funded_rewards = core::cmp::min(needed_rewards.clone(), remaining_rewards);
+        unfunded_rewards_mapper.set(&needed_rewards - &funded_rewards);
 
         storage_cache.reward_reserve += &total_reward;
-        accumulated_rewards += &total_reward;
+        accumulated_rewards += &funded_rewards;
         accumulated_rewards_mapper.set(&accumulated_rewards);
 
+        let mut user_rewards = split_rewards.users;
+        sc.collect_guild_master_commission(
+            &mut user_rewards,
+            minted_rewards.guild_master_commission,
+        );
+        sc.collect_boosted_yields_rewards(&mut user_rewards);
+
         if storage_cache.farm_token_supply == 0 {
-            sc.update_all();
+            Self::process_expired_locks_if_synced(sc, &storage_cache.user_rps);
+            Self::update_config_if_synced(sc);
 
             return;
         }
@@ -169,12 +285,14 @@ pub trait FarmContract {
 
         let total_user_tokens = &sc.farm_token_supply().get() - &guild_master_tokens;
         if total_user_tokens > 0 {
-            let increase_users = (split_rewards.users * &storage_cache.division_safety_constant)
-                / &total_user_tokens;
+            let total_user_weight = total_user_tokens + sc.user_lock_extra_weight().get();
+            let increase_users =
+                (user_rewards * &storage_cache.division_safety_constant) / &total_user_weight;
             storage_cache.user_rps += increase_users;
         }
 
-        sc.update_all();
+        Self::process_expired_locks_if_synced(sc, &storage_cache.user_rps);
+        Self::update_config_if_synced(sc);
     }
 
     fn calculate_rewards(
@@ -191,7 +309,18 @@ pub trait FarmContract {
         }
 
         let rps_diff = storage_rps - &token_rps;
-        farm_token_amount * &rps_diff / &storage_cache.division_safety_constant
+        let rewards = farm_token_amount * &rps_diff / &storage_cache.division_safety_constant;
+        if caller == &sc.guild_master_address().get() {
+            return rewards;
+        }
+
+        rewards
+            + sc.calculate_lock_extra_rewards(
+                farm_token_amount,
+                token_attributes,
+                storage_rps,
+                &storage_cache.division_safety_constant,
+            )
     }
 
     fn create_enter_farm_initial_attributes(
@@ -202,6 +331,7 @@ pub trait FarmContract {
             reward_per_share: current_reward_per_share,
             compounded_reward: BigUint::zero(),
             current_farm_amount: farming_token_amount,
+            lock: None,
         }
     }
 
@@ -213,6 +343,7 @@ pub trait FarmContract {
             reward_per_share: current_reward_per_share,
             compounded_reward: first_token_attributes.compounded_reward,
             current_farm_amount: first_token_attributes.current_farm_amount,
+            lock: first_token_attributes.lock,
         }
     }
 
@@ -227,6 +358,7 @@ pub trait FarmContract {
             reward_per_share: current_reward_per_share,
             compounded_reward: new_pos_compounded_reward,
             current_farm_amount: new_pos_current_farm_amount,
+            lock: first_token_attributes.lock,
         }
     }
 }
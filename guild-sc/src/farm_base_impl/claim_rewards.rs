@@ -85,6 +85,7 @@ pub trait BaseClaimRewardsModule:
             &farm_token_mapper,
         );
         new_token_attributes.set_reward_per_share(rps.clone());
+        new_token_attributes.clear_expired_lock(self.blockchain().get_block_epoch());
 
         let first_farm_token = &temp_result.context.first_farm_token.payment;
         farm_token_mapper.nft_burn(first_farm_token.token_nonce, &first_farm_token.amount);
@@ -126,16 +127,17 @@ pub trait BaseClaimRewardsModule:
 
         FC::generate_aggregated_rewards(self, &mut storage_cache);
 
-        let mut total_rewards =
+        let mut accrued_rewards =
             self.get_first_token_rewards::<FC>(caller, &storage_cache, &claim_rewards_context);
         self.add_additional_token_rewards::<FC>(
-            &mut total_rewards,
+            &mut accrued_rewards,
             caller,
             &storage_cache,
             &claim_rewards_context,
         );
 
-        storage_cache.reward_reserve -= &total_rewards;
+        let total_rewards =
+            self.take_payable_rewards(caller, accrued_rewards, &mut storage_cache.reward_reserve);
 
         TempInternalClaimRewardsResult {
             context: claim_rewards_context,
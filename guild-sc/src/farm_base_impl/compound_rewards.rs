@@ -63,6 +63,7 @@ pub trait BaseCompoundRewardsModule:
             &farm_token_mapper,
         );
         new_token_attributes.set_reward_per_share(rps.clone());
+        new_token_attributes.clear_expired_lock(self.blockchain().get_block_epoch());
 
         let new_farm_token = farm_token_mapper.nft_create(
             new_token_attributes.get_total_supply(),
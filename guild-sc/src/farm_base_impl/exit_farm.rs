@@ -60,14 +60,15 @@ pub trait BaseExitFarmModule:
             .clone()
             .into_part(farm_token_amount);
 
-        let rewards = FC::calculate_rewards(
+        let accrued_rewards = FC::calculate_rewards(
             self,
             &caller,
             farm_token_amount,
             &token_attributes,
             &storage_cache,
         );
-        storage_cache.reward_reserve -= &rewards;
+        let rewards =
+            self.take_payable_rewards(&caller, accrued_rewards, &mut storage_cache.reward_reserve);
 
         let farming_token_amount = token_attributes.get_total_supply();
         let farming_token_payment = EsdtTokenPayment::new(
@@ -0,0 +1,124 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use common_structs::Percent;
+use guild_sc_config::checkpoints::Timestamp;
+
+use crate::custom_rewards::MAX_PERCENT;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct PendingCommission {
+    pub percentage: Percent,
+    pub effective_timestamp: Timestamp,
+}
+
+#[multiversx_sc::module]
+pub trait GuildMasterCommissionModule:
+    crate::tiered_rewards::read_config::ReadConfigModule
+{
+    /// Moves the guild master's commission out of the users' rewards
+    fn collect_guild_master_commission(&self, user_rewards: &mut BigUint, commission: BigUint) {
+        if commission == 0 {
+            return;
+        }
+
+        self.accumulated_guild_master_commission()
+            .update(|total| *total += &commission);
+
+        *user_rewards -= commission;
+    }
+
+    /// Commission on users' rewards accrued from the given timestamp on
+    fn calculate_guild_master_commission_cut(
+        &self,
+        user_rewards: &BigUint,
+        timestamp: Timestamp,
+    ) -> BigUint {
+        let percentage = self.get_guild_master_commission_at(timestamp);
+        if percentage == 0 {
+            return BigUint::zero();
+        }
+
+        user_rewards * percentage / MAX_PERCENT
+    }
+
+    /// Decreases apply immediately, increases only after the notice period
+    fn set_guild_master_commission(&self, percentage: Percent) {
+        require!(
+            percentage <= self.get_max_guild_master_commission(),
+            "Commission too high"
+        );
+
+        self.apply_pending_guild_master_commission();
+
+        let commission_mapper = self.guild_master_commission();
+        let pending_mapper = self.pending_guild_master_commission();
+        if percentage <= commission_mapper.get() {
+            commission_mapper.set(percentage);
+            pending_mapper.clear();
+
+            return;
+        }
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let notice_seconds = self.get_commission_notice_seconds();
+        pending_mapper.set(PendingCommission {
+            percentage,
+            effective_timestamp: current_timestamp + notice_seconds,
+        });
+    }
+
+    fn apply_pending_guild_master_commission(&self) {
+        let pending_mapper = self.pending_guild_master_commission();
+        if pending_mapper.is_empty() {
+            return;
+        }
+
+        let pending = pending_mapper.get();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        if pending.effective_timestamp > current_timestamp {
+            return;
+        }
+
+        self.guild_master_commission().set(pending.percentage);
+        pending_mapper.clear();
+    }
+
+    /// The commission currently applied, bounded by the global maximum
+    #[view(getGuildMasterCommission)]
+    fn get_guild_master_commission(&self) -> Percent {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        self.get_guild_master_commission_at(current_timestamp)
+    }
+
+    fn get_guild_master_commission_at(&self, timestamp: Timestamp) -> Percent {
+        let mut percentage = self.guild_master_commission().get();
+        if let Some(effective_timestamp) = self.get_pending_guild_master_commission_timestamp() {
+            if effective_timestamp <= timestamp {
+                percentage = self.pending_guild_master_commission().get().percentage;
+            }
+        }
+
+        core::cmp::min(percentage, self.get_max_guild_master_commission())
+    }
+
+    fn get_pending_guild_master_commission_timestamp(&self) -> Option<Timestamp> {
+        let pending_mapper = self.pending_guild_master_commission();
+        if pending_mapper.is_empty() {
+            return None;
+        }
+
+        Some(pending_mapper.get().effective_timestamp)
+    }
+
+    #[storage_mapper("guildMasterCommission")]
+    fn guild_master_commission(&self) -> SingleValueMapper<Percent>;
+
+    #[view(getPendingGuildMasterCommission)]
+    #[storage_mapper("pendingGuildMasterCommission")]
+    fn pending_guild_master_commission(&self) -> SingleValueMapper<PendingCommission>;
+
+    #[view(getAccumulatedGuildMasterCommission)]
+    #[storage_mapper("accumulatedGuildMasterCommission")]
+    fn accumulated_guild_master_commission(&self) -> SingleValueMapper<BigUint>;
+}
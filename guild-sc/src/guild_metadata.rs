@@ -0,0 +1,70 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+pub const MAX_NAME_LEN: usize = 50;
+pub const MAX_DESCRIPTION_LEN: usize = 280;
+pub const MAX_URI_LEN: usize = 200;
+pub const MAX_SOCIAL_LINKS: usize = 5;
+pub const MAX_TAGS: usize = 5;
+pub const MAX_TAG_LEN: usize = 20;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct GuildMetadata<M: ManagedTypeApi> {
+    pub name: ManagedBuffer<M>,
+    pub description: ManagedBuffer<M>,
+    pub website: ManagedBuffer<M>,
+    pub social_links: ManagedVec<M, ManagedBuffer<M>>,
+    pub logo_uri: ManagedBuffer<M>,
+    pub tags: ManagedVec<M, ManagedBuffer<M>>,
+}
+
+/// Name, description, links and logo shown by frontends for the guild.
+/// The factory admins may hide the metadata of a guild.
+#[multiversx_sc::module]
+pub trait GuildMetadataModule: crate::tiered_rewards::read_config::ReadConfigModule {
+    #[endpoint(setGuildMetadata)]
+    fn set_guild_metadata(&self, metadata: GuildMetadata<Self::Api>) {
+        let guild_master = self.guild_master_address().get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == guild_master,
+            "Only guild master may set guild metadata"
+        );
+
+        self.require_valid_guild_metadata(&metadata);
+
+        self.guild_metadata().set(metadata);
+    }
+
+    fn require_valid_guild_metadata(&self, metadata: &GuildMetadata<Self::Api>) {
+        require!(
+            !metadata.name.is_empty() && metadata.name.len() <= MAX_NAME_LEN,
+            "Invalid name"
+        );
+        require!(
+            metadata.description.len() <= MAX_DESCRIPTION_LEN,
+            "Description too long"
+        );
+        require!(
+            metadata.website.len() <= MAX_URI_LEN && metadata.logo_uri.len() <= MAX_URI_LEN,
+            "Link too long"
+        );
+
+        require!(
+            metadata.social_links.len() <= MAX_SOCIAL_LINKS,
+            "Too many social links"
+        );
+        for link in &metadata.social_links {
+            require!(link.len() <= MAX_URI_LEN, "Link too long");
+        }
+
+        require!(metadata.tags.len() <= MAX_TAGS, "Too many tags");
+        for tag in &metadata.tags {
+            require!(!tag.is_empty() && tag.len() <= MAX_TAG_LEN, "Invalid tag");
+        }
+    }
+
+    /// Only exposed through the factory, which applies the admins' hidden flag
+    #[storage_mapper("guildMetadata")]
+    fn guild_metadata(&self) -> SingleValueMapper<GuildMetadata<Self::Api>>;
+}
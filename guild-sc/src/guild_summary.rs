@@ -0,0 +1,72 @@
+use common_structs::Percent;
+use pausable::State;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    ManagedVecItem,
+    Clone,
+    PartialEq,
+    Debug,
+)]
+pub struct GuildSummary<M: ManagedTypeApi> {
+    pub total_staked: BigUint<M>,
+    pub member_count: usize,
+    pub user_apr: Percent,
+    pub guild_master_apr: Percent,
+    pub remaining_rewards: BigUint<M>,
+    pub active: bool,
+    pub closing: bool,
+}
+
+/// Everything the factory's discovery views need from a guild, in a single call
+#[multiversx_sc::module]
+pub trait GuildSummaryModule:
+    crate::custom_rewards::CustomRewardsModule
+    + crate::rewards::RewardsModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + crate::tokens::request_id::RequestIdModule
+    + utils::UtilsModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+    + crate::user_actions::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + crate::guild_master_commission::GuildMasterCommissionModule
+    + crate::locked_positions::LockedPositionsModule
+{
+    #[view(getGuildSummary)]
+    fn get_guild_summary(&self) -> GuildSummary<Self::Api> {
+        let total_staked = self.total_base_staked_tokens().get();
+        let guild_master_apr = self.find_guild_master_tier_apr(&total_staked);
+        let user_apr = self.find_user_tier_apr(self.get_total_staked_percent());
+
+        let reward_capacity = self.reward_capacity().get();
+        let accumulated_rewards = self.accumulated_rewards().get();
+        let remaining_rewards = if reward_capacity > accumulated_rewards {
+            reward_capacity - accumulated_rewards
+        } else {
+            BigUint::zero()
+        };
+
+        GuildSummary {
+            total_staked,
+            member_count: self.member_count().get(),
+            user_apr,
+            guild_master_apr,
+            remaining_rewards,
+            active: self.state().get() == State::Active,
+            closing: self.guild_closing().get(),
+        }
+    }
+}
@@ -3,20 +3,23 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
-use crate::farm_base_impl::base_traits_impl::FarmContract;
 use common_errors::ERROR_NOT_AN_ESDT;
-use contexts::storage_cache::StorageCache;
-use farm_base_impl::base_traits_impl::FarmStakingWrapper;
 use fixed_supply_token::FixedSupplyToken;
 use pausable::State;
 use permissions_module::Permissions;
 use tokens::token_attributes::StakingFarmTokenAttributes;
 
+pub mod boosted_yields;
 pub mod config;
 pub mod contexts;
 pub mod custom_rewards;
 pub mod events;
 pub mod farm_base_impl;
+pub mod guild_master_commission;
+pub mod guild_metadata;
+pub mod guild_summary;
+pub mod locked_positions;
+pub mod pending_rewards;
 pub mod rewards;
 pub mod tiered_rewards;
 pub mod tokens;
@@ -52,11 +55,27 @@ pub trait FarmStaking:
     + user_actions::migration::MigrationModule
     + user_actions::custom_events::CustomEventsModule
     + user_actions::close_guild::CloseGuildModule
+    + boosted_yields::BoostedYieldsModule
+    + user_actions::transfer_position::TransferPositionModule
+    + user_actions::guild_master_handover::GuildMasterHandoverModule
+    + pending_rewards::PendingRewardsModule
+    + user_actions::compound_vault::CompoundVaultModule
+    + guild_master_commission::GuildMasterCommissionModule
+    + guild_metadata::GuildMetadataModule
+    + guild_summary::GuildSummaryModule
+    + user_actions::instant_unbond::InstantUnbondModule
+    + locked_positions::LockedPositionsModule
+    + user_actions::lock_position::LockPositionModule
+    + user_actions::referrals::ReferralsModule
+    + user_actions::guild_access::GuildAccessModule
+    + user_actions::delegates::DelegatesModule
+    + user_actions::relayed_actions::RelayedActionsModule
 {
     #[init]
     fn init(
         &self,
         farming_token_id: TokenIdentifier,
+        reward_token_id: TokenIdentifier,
         division_safety_constant: BigUint,
         config_sc_address: ManagedAddress,
         guild_master: ManagedAddress,
@@ -66,9 +85,8 @@ pub trait FarmStaking:
         admins.push(guild_master.clone());
         admins.push(guild_factory);
 
-        // farming and reward token are the same
         self.base_farm_init(
-            farming_token_id.clone(),
+            reward_token_id,
             farming_token_id,
             division_safety_constant,
             admins,
@@ -79,11 +97,23 @@ pub trait FarmStaking:
         self.config_sc_address().set(config_sc_address);
         self.guild_master_address().set(guild_master);
 
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.first_week_start_epoch().set(current_epoch);
+
         self.update_all();
     }
 
     #[upgrade]
-    fn upgrade(&self) {}
+    fn upgrade(&self) {
+        let first_week_start_epoch_mapper = self.first_week_start_epoch();
+        if first_week_start_epoch_mapper.is_empty() {
+            let current_epoch = self.blockchain().get_block_epoch();
+            first_week_start_epoch_mapper.set(current_epoch);
+        }
+
+        self.migrate_to_timestamp_accrual();
+        self.update_all();
+    }
 
     #[payable("*")]
     #[endpoint(mergeFarmTokens)]
@@ -129,15 +159,14 @@ pub trait FarmStaking:
         farm_token_amount: BigUint,
         attributes: StakingFarmTokenAttributes<Self::Api>,
     ) -> BigUint {
-        let mut storage_cache = StorageCache::new(self);
-        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+        let reward_per_share = self.get_projected_rps_for_user(&user);
+        let is_guild_master = user == self.guild_master_address().get();
 
-        FarmStakingWrapper::<Self>::calculate_rewards(
-            self,
-            &user,
+        self.calculate_position_rewards(
             &farm_token_amount,
             &attributes,
-            &storage_cache,
+            &reward_per_share,
+            is_guild_master,
         )
     }
 
@@ -0,0 +1,130 @@
+multiversx_sc::imports!();
+
+use common_structs::Epoch;
+
+use crate::{
+    custom_rewards::MAX_PERCENT,
+    tokens::token_attributes::{PositionLock, StakingFarmTokenAttributes},
+};
+
+/// Locked positions count with an extra weight towards the users' reward share.
+/// The extra weight is removed by the first accrual after the lock expires.
+#[multiversx_sc::module]
+pub trait LockedPositionsModule:
+    crate::rewards::RewardsModule
+    + crate::config::ConfigModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + crate::tokens::request_id::RequestIdModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    fn calculate_lock_extra_weight(&self, amount: &BigUint, lock: &PositionLock) -> BigUint {
+        amount * (lock.multiplier - MAX_PERCENT) / MAX_PERCENT
+    }
+
+    fn add_position_lock_weight(&self, amount: &BigUint, lock: &PositionLock) {
+        let extra_weight = self.calculate_lock_extra_weight(amount, lock);
+        if extra_weight == 0 {
+            return;
+        }
+
+        self.user_lock_extra_weight()
+            .update(|weight| *weight += &extra_weight);
+        self.expiring_lock_extra_weight(lock.unlock_epoch)
+            .update(|weight| *weight += &extra_weight);
+        let _ = self.lock_expiry_epochs().insert(lock.unlock_epoch);
+    }
+
+    fn remove_position_lock_weight(&self, amount: &BigUint, lock: &PositionLock) {
+        let extra_weight = self.calculate_lock_extra_weight(amount, lock);
+        if extra_weight == 0 {
+            return;
+        }
+
+        self.user_lock_extra_weight()
+            .update(|weight| *weight -= &extra_weight);
+        self.expiring_lock_extra_weight(lock.unlock_epoch)
+            .update(|weight| *weight -= &extra_weight);
+    }
+
+    /// Removes the weight of the locks expired since the last check.
+    /// The reward per share at expiry is kept for the last claim of those positions.
+    fn process_expired_locks(&self, user_rps: &BigUint) {
+        let current_epoch = self.blockchain().get_block_epoch();
+        let last_check_mapper = self.last_lock_expiry_check_epoch();
+        if last_check_mapper.get() == current_epoch {
+            return;
+        }
+
+        last_check_mapper.set(current_epoch);
+
+        let mut expiry_epochs_mapper = self.lock_expiry_epochs();
+        let mut expired_epochs = ManagedVec::<Self::Api, Epoch>::new();
+        for unlock_epoch in expiry_epochs_mapper.iter() {
+            if unlock_epoch <= current_epoch {
+                expired_epochs.push(unlock_epoch);
+            }
+        }
+
+        for unlock_epoch in &expired_epochs {
+            let extra_weight = self.expiring_lock_extra_weight(unlock_epoch).take();
+            self.user_lock_extra_weight()
+                .update(|weight| *weight -= extra_weight);
+            self.rps_at_lock_expiry(unlock_epoch).set(user_rps);
+            let _ = expiry_epochs_mapper.swap_remove(&unlock_epoch);
+        }
+    }
+
+    /// Rewards of the position's extra weight, up to the lock expiry
+    fn calculate_lock_extra_rewards(
+        &self,
+        farm_token_amount: &BigUint,
+        attributes: &StakingFarmTokenAttributes<Self::Api>,
+        user_rps: &BigUint,
+        division_safety_constant: &BigUint,
+    ) -> BigUint {
+        let lock = match &attributes.lock {
+            Some(lock) => lock,
+            None => return BigUint::zero(),
+        };
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let end_rps = if lock.is_active(current_epoch) {
+            user_rps.clone()
+        } else {
+            let expiry_rps_mapper = self.rps_at_lock_expiry(lock.unlock_epoch);
+            if !expiry_rps_mapper.is_empty() {
+                expiry_rps_mapper.get()
+            } else {
+                // expiry not processed yet, it is recorded after the next accrual
+                user_rps.clone()
+            }
+        };
+        if end_rps <= attributes.reward_per_share {
+            return BigUint::zero();
+        }
+
+        let extra_weight = self.calculate_lock_extra_weight(farm_token_amount, lock);
+        let rps_diff = end_rps - &attributes.reward_per_share;
+
+        extra_weight * rps_diff / division_safety_constant
+    }
+
+    #[view(getUserLockExtraWeight)]
+    #[storage_mapper("userLockExtraWeight")]
+    fn user_lock_extra_weight(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("expiringLockExtraWeight")]
+    fn expiring_lock_extra_weight(&self, unlock_epoch: Epoch) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("lockExpiryEpochs")]
+    fn lock_expiry_epochs(&self) -> UnorderedSetMapper<Epoch>;
+
+    #[storage_mapper("rpsAtLockExpiry")]
+    fn rps_at_lock_expiry(&self, unlock_epoch: Epoch) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("lastLockExpiryCheckEpoch")]
+    fn last_lock_expiry_check_epoch(&self) -> SingleValueMapper<Epoch>;
+}
@@ -0,0 +1,240 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use common_structs::{Nonce, Percent};
+use guild_sc_config::checkpoints::Timestamp;
+
+use crate::farm_base_impl::base_traits_impl::{
+    FarmContract, FarmStakingWrapper, RewardsProjection,
+};
+use crate::tokens::token_attributes::StakingFarmTokenAttributes;
+
+pub type PositionMultiValue<M> = MultiValue2<Nonce, BigUint<M>>;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct PendingRewards<M: ManagedTypeApi> {
+    pub is_guild_master: bool,
+    pub base_rewards: BigUint<M>,
+    pub apr_bounded_rewards: BigUint<M>,
+    pub reward_per_share: BigUint<M>,
+    pub claimable_rewards: BigUint<M>,
+    pub held_back_rewards: BigUint<M>,
+    pub boosted_rewards: BigUint<M>,
+    pub guild_master_commission: Percent,
+}
+
+pub struct ProjectedRewardPerShare<M: ManagedTypeApi> {
+    pub user_rps: BigUint<M>,
+    pub guild_master_rps: BigUint<M>,
+    pub projection: RewardsProjection<M>,
+}
+
+#[multiversx_sc::module]
+pub trait PendingRewardsModule:
+    crate::custom_rewards::CustomRewardsModule
+    + crate::rewards::RewardsModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + crate::tokens::request_id::RequestIdModule
+    + utils::UtilsModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+    + crate::user_actions::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + crate::guild_master_commission::GuildMasterCommissionModule
+    + crate::locked_positions::LockedPositionsModule
+{
+    /// Simulates claiming the given (nonce, amount) farm token positions, without writing to storage.
+    /// `base_rewards` and `apr_bounded_rewards` are the next rewards step of the user's track,
+    /// before and after applying the APR cap.
+    #[view(getPendingRewards)]
+    fn get_pending_rewards(
+        &self,
+        user: ManagedAddress,
+        positions: MultiValueEncoded<PositionMultiValue<Self::Api>>,
+    ) -> PendingRewards<Self::Api> {
+        let guild_master = self.guild_master_address().get();
+        let is_guild_master = user == guild_master;
+        let projected = self.project_reward_per_share();
+        let (reward_per_share, base_rewards, apr_bounded_rewards) = if is_guild_master {
+            (
+                projected.guild_master_rps,
+                projected.projection.unbounded.guild_master,
+                projected.projection.apr_bounded.guild_master,
+            )
+        } else {
+            (
+                projected.user_rps,
+                projected.projection.unbounded.users,
+                projected.projection.apr_bounded.users,
+            )
+        };
+
+        let farm_token_mapper = self.farm_token();
+        let mut claimable_rewards = BigUint::zero();
+        for position in positions {
+            let (nonce, amount) = position.into_tuple();
+            let attributes: StakingFarmTokenAttributes<Self::Api> =
+                farm_token_mapper.get_token_attributes(nonce);
+            require!(
+                amount <= attributes.current_farm_amount,
+                "Invalid position amount"
+            );
+
+            claimable_rewards += self.calculate_position_rewards(
+                &amount,
+                &attributes,
+                &reward_per_share,
+                is_guild_master,
+            );
+        }
+
+        let held_back_rewards = self.held_back_rewards(&user).get();
+        let boosted_rewards = self.get_pending_boosted_yields_rewards(&user);
+        let guild_master_commission = self.get_guild_master_commission();
+
+        PendingRewards {
+            is_guild_master,
+            base_rewards,
+            apr_bounded_rewards,
+            reward_per_share,
+            claimable_rewards,
+            held_back_rewards,
+            boosted_rewards,
+            guild_master_commission,
+        }
+    }
+
+    fn calculate_position_rewards(
+        &self,
+        farm_token_amount: &BigUint,
+        attributes: &StakingFarmTokenAttributes<Self::Api>,
+        reward_per_share: &BigUint,
+        is_guild_master: bool,
+    ) -> BigUint {
+        if reward_per_share <= &attributes.reward_per_share {
+            return BigUint::zero();
+        }
+
+        let rps_diff = reward_per_share - &attributes.reward_per_share;
+        let division_safety_constant = self.division_safety_constant().get();
+        let rewards = farm_token_amount * &rps_diff / &division_safety_constant;
+        if is_guild_master {
+            return rewards;
+        }
+
+        rewards
+            + self.calculate_lock_extra_rewards(
+                farm_token_amount,
+                attributes,
+                reward_per_share,
+                &division_safety_constant,
+            )
+    }
+
+    fn get_projected_rps_for_user(&self, user: &ManagedAddress) -> BigUint {
+        let projected = self.project_reward_per_share();
+        let guild_master = self.guild_master_address().get();
+        if user != &guild_master {
+            projected.user_rps
+        } else {
+            projected.guild_master_rps
+        }
+    }
+
+    /// Read-only version of `generate_aggregated_rewards`
+    fn project_reward_per_share(&self) -> ProjectedRewardPerShare<Self::Api> {
+        let mut result = ProjectedRewardPerShare {
+            user_rps: self.user_rps().get(),
+            guild_master_rps: self.guild_master_rps().get(),
+            projection: RewardsProjection::zero(),
+        };
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let last_reward_timestamp = self.last_reward_timestamp().get();
+        if current_timestamp <= last_reward_timestamp || self.guild_closing().get() {
+            return result;
+        }
+
+        result.projection = FarmStakingWrapper::<Self>::calculate_rewards_projection(
+            self,
+            current_timestamp,
+            last_reward_timestamp,
+        );
+
+        let split_rewards = result.projection.bounded();
+
+        let farm_token_supply = self.farm_token_supply().get();
+        if farm_token_supply == 0 {
+            return result;
+        }
+
+        let division_safety_constant = self.division_safety_constant().get();
+        let guild_master_tokens = self.guild_master_tokens().get();
+        if guild_master_tokens > 0 {
+            result.guild_master_rps +=
+                split_rewards.guild_master * &division_safety_constant / &guild_master_tokens;
+        }
+
+        let total_user_tokens = farm_token_supply - guild_master_tokens;
+        if total_user_tokens > 0 {
+            let total_user_weight = total_user_tokens + self.user_lock_extra_weight().get();
+            let mut user_rewards = self.project_user_rewards_after_commission(
+                split_rewards.users,
+                last_reward_timestamp,
+                current_timestamp,
+            );
+            let boosted_rewards = self.calculate_boosted_yields_cut(&user_rewards);
+            user_rewards -= boosted_rewards;
+            result.user_rps += user_rewards * &division_safety_constant / &total_user_weight;
+        }
+
+        result
+    }
+
+    /// A pending commission increase only applies to the rewards accrued after it
+    fn project_user_rewards_after_commission(
+        &self,
+        user_rewards: BigUint,
+        last_reward_timestamp: Timestamp,
+        current_timestamp: Timestamp,
+    ) -> BigUint {
+        match self.get_pending_guild_master_commission_timestamp() {
+            Some(effective_timestamp)
+                if effective_timestamp > last_reward_timestamp
+                    && effective_timestamp < current_timestamp =>
+            {
+                let rewards_before = FarmStakingWrapper::<Self>::calculate_rewards_projection(
+                    self,
+                    effective_timestamp,
+                    last_reward_timestamp,
+                )
+                .bounded()
+                .users;
+                let rewards_after = FarmStakingWrapper::<Self>::calculate_rewards_projection(
+                    self,
+                    current_timestamp,
+                    effective_timestamp,
+                )
+                .bounded()
+                .users;
+                let commission_before = self
+                    .calculate_guild_master_commission_cut(&rewards_before, last_reward_timestamp);
+                let commission_after =
+                    self.calculate_guild_master_commission_cut(&rewards_after, effective_timestamp);
+
+                rewards_before - commission_before + rewards_after - commission_after
+            }
+            _ => {
+                let commission = self
+                    .calculate_guild_master_commission_cut(&user_rewards, last_reward_timestamp);
+
+                user_rewards - commission
+            }
+        }
+    }
+}
@@ -14,20 +14,20 @@ pub trait RewardsModule:
 {
     fn start_produce_rewards(&self) {
         require!(
-            self.per_block_reward_amount().get() != 0u64,
+            self.per_second_reward_amount().get() != 0u64,
             "Cannot produce zero reward amount"
         );
         require!(
             !self.produce_rewards_enabled().get(),
             "Producing rewards is already enabled"
         );
-        let current_nonce = self.blockchain().get_block_nonce();
+        let current_timestamp = self.blockchain().get_block_timestamp();
         self.produce_rewards_enabled().set(true);
-        self.last_reward_block_nonce().set(current_nonce);
+        self.last_reward_timestamp().set(current_timestamp);
     }
 
     #[inline]
-    fn produces_per_block_rewards(&self) -> bool {
+    fn produces_per_second_rewards(&self) -> bool {
         self.produce_rewards_enabled().get()
     }
 
@@ -44,6 +44,30 @@ pub trait RewardsModule:
         }
     }
 
+    /// Rewards always accrue in full, but only the part funded by the factory is paid out.
+    /// The rest is held back for the user until the guild receives the missing rewards.
+    fn take_payable_rewards(
+        &self,
+        user: &ManagedAddress,
+        rewards: BigUint,
+        reward_reserve: &mut BigUint,
+    ) -> BigUint {
+        let held_back_mapper = self.held_back_rewards(user);
+        let owed_rewards = held_back_mapper.get() + rewards;
+        let unfunded_rewards = self.unfunded_rewards().get();
+        let funded_reserve = if *reward_reserve > unfunded_rewards {
+            &*reward_reserve - &unfunded_rewards
+        } else {
+            BigUint::zero()
+        };
+
+        let payable_rewards = core::cmp::min(owed_rewards.clone(), funded_reserve);
+        *reward_reserve -= &payable_rewards;
+        held_back_mapper.set(owed_rewards - &payable_rewards);
+
+        payable_rewards
+    }
+
     #[view(getGuildMasterRewardPerShare)]
     #[storage_mapper("guildMasterRps")]
     fn guild_master_rps(&self) -> SingleValueMapper<BigUint>;
@@ -55,4 +79,13 @@ pub trait RewardsModule:
     #[view(getRewardReserve)]
     #[storage_mapper("reward_reserve")]
     fn reward_reserve(&self) -> SingleValueMapper<BigUint>;
+
+    /// Rewards already accrued, which the factory could not fund yet
+    #[view(getUnfundedRewards)]
+    #[storage_mapper("unfundedRewards")]
+    fn unfunded_rewards(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getHeldBackRewards)]
+    #[storage_mapper("heldBackRewards")]
+    fn held_back_rewards(&self, user: &ManagedAddress) -> SingleValueMapper<BigUint>;
 }
@@ -1,7 +1,9 @@
 use common_structs::{Epoch, Percent};
 use guild_sc_config::{
-    global_config::{GlobalPauseStatus, UNPAUSED},
-    tier_types::{GuildMasterRewardTier, RewardTier, UserRewardTier},
+    checkpoints::{ConfigCheckpoint, Timestamp},
+    emission::EmissionSchedule,
+    global_config::{BoostedYieldsFactors, GlobalPauseStatus, UNPAUSED},
+    tier_types::{GuildMasterRewardTier, RewardCurve, RewardTier, UserRewardTier},
 };
 
 multiversx_sc::imports!();
@@ -24,6 +26,43 @@ pub trait ReadConfigModule {
         mapper.get(last_index)
     }
 
+    /// Interpolates linearly between the APRs of consecutive tiers
+    fn interpolate_tier_apr<T: TopEncode + TopDecode + RewardTier<Self::Api>>(
+        &self,
+        value: &BigUint,
+        mapper: &VecMapper<T>,
+    ) -> Percent {
+        let mut opt_prev_tier = Option::<T>::None;
+        for reward_tier in mapper.iter() {
+            let max_value = reward_tier.get_max_value();
+            if value > &max_value {
+                opt_prev_tier = Some(reward_tier);
+                continue;
+            }
+
+            let prev_tier = match opt_prev_tier {
+                Some(prev_tier) => prev_tier,
+                None => return reward_tier.get_apr(),
+            };
+
+            let prev_max_value = prev_tier.get_max_value();
+            let prev_apr = prev_tier.get_apr();
+            let apr = reward_tier.get_apr();
+            let range = &max_value - &prev_max_value;
+            let progress = value - &prev_max_value;
+            if apr >= prev_apr {
+                let apr_increase = BigUint::from(apr - prev_apr) * progress / range;
+                return prev_apr + apr_increase.to_u64().unwrap_or_default();
+            } else {
+                let apr_decrease = BigUint::from(prev_apr - apr) * progress / range;
+                return prev_apr - apr_decrease.to_u64().unwrap_or_default();
+            }
+        }
+
+        let last_index = mapper.len();
+        mapper.get(last_index).get_apr()
+    }
+
     fn get_guild_master_tiers_mapper(
         &self,
     ) -> VecMapper<GuildMasterRewardTier<Self::Api>, ManagedAddress> {
@@ -36,11 +75,70 @@ pub trait ReadConfigModule {
         self.external_user_tiers(config_addr)
     }
 
+    fn get_scheduled_guild_master_tiers_mapper(
+        &self,
+    ) -> VecMapper<GuildMasterRewardTier<Self::Api>, ManagedAddress> {
+        let config_addr = self.config_sc_address().get();
+        self.external_scheduled_guild_master_tiers(config_addr)
+    }
+
+    fn get_scheduled_user_tiers_mapper(&self) -> VecMapper<UserRewardTier, ManagedAddress> {
+        let config_addr = self.config_sc_address().get();
+        self.external_scheduled_user_tiers(config_addr)
+    }
+
+    fn get_scheduled_guild_master_tiers_timestamp(&self) -> Option<Timestamp> {
+        let config_addr = self.config_sc_address().get();
+        let mapper = self.external_scheduled_guild_master_tiers_timestamp(config_addr);
+        if mapper.is_empty() {
+            return None;
+        }
+
+        Some(mapper.get())
+    }
+
+    fn get_scheduled_user_tiers_timestamp(&self) -> Option<Timestamp> {
+        let config_addr = self.config_sc_address().get();
+        let mapper = self.external_scheduled_user_tiers_timestamp(config_addr);
+        if mapper.is_empty() {
+            return None;
+        }
+
+        Some(mapper.get())
+    }
+
+    fn get_config_checkpoints_mapper(
+        &self,
+    ) -> VecMapper<ConfigCheckpoint<Self::Api>, ManagedAddress> {
+        let config_addr = self.config_sc_address().get();
+        self.external_config_checkpoints(config_addr)
+    }
+
+    fn get_guild_master_reward_curve(&self) -> RewardCurve {
+        let config_addr = self.config_sc_address().get();
+        self.external_guild_master_reward_curve(config_addr).get()
+    }
+
+    fn get_user_reward_curve(&self) -> RewardCurve {
+        let config_addr = self.config_sc_address().get();
+        self.external_user_reward_curve(config_addr).get()
+    }
+
     fn get_max_staked_tokens(&self) -> BigUint {
         let config_addr = self.config_sc_address().get();
         self.external_max_staked_tokens(config_addr).get()
     }
 
+    fn get_max_guild_master_commission(&self) -> Percent {
+        let config_addr = self.config_sc_address().get();
+        self.external_max_guild_master_commission(config_addr).get()
+    }
+
+    fn get_commission_notice_seconds(&self) -> Timestamp {
+        let config_addr = self.config_sc_address().get();
+        self.external_commission_notice_seconds(config_addr).get()
+    }
+
     fn get_min_unbond_epochs_user(&self) -> Epoch {
         let config_addr = self.config_sc_address().get();
         self.external_min_unbond_epochs_user(config_addr).get()
@@ -52,6 +150,26 @@ pub trait ReadConfigModule {
             .get()
     }
 
+    fn get_instant_unbond_fee(&self) -> Option<Percent> {
+        let config_addr = self.config_sc_address().get();
+        let mapper = self.external_instant_unbond_fee(config_addr);
+        if mapper.is_empty() {
+            return None;
+        }
+
+        Some(mapper.get())
+    }
+
+    fn get_lock_multiplier(&self, lock_epochs: Epoch) -> Option<Percent> {
+        let config_addr = self.config_sc_address().get();
+        let mapper = self.external_lock_multiplier(config_addr, lock_epochs);
+        if mapper.is_empty() {
+            return None;
+        }
+
+        Some(mapper.get())
+    }
+
     fn get_min_stake_user(&self) -> BigUint {
         let config_addr = self.config_sc_address().get();
         self.external_min_stake_user(config_addr).get()
@@ -71,14 +189,14 @@ pub trait ReadConfigModule {
         }
     }
 
-    fn get_seconds_per_block(&self) -> u64 {
+    fn get_per_second_reward_amount(&self) -> BigUint {
         let config_addr = self.config_sc_address().get();
-        self.external_seconds_per_block(config_addr).get()
+        self.external_per_second_reward_amount(config_addr).get()
     }
 
-    fn get_per_block_reward_amount(&self) -> BigUint {
+    fn get_emission_schedule(&self) -> EmissionSchedule<Self::Api> {
         let config_addr = self.config_sc_address().get();
-        self.external_per_block_reward_amount(config_addr).get()
+        self.external_emission_schedule(config_addr).get()
     }
 
     fn get_total_staking_token_minted(&self) -> BigUint {
@@ -111,6 +229,26 @@ pub trait ReadConfigModule {
         self.external_tokens_decimals(config_addr).get()
     }
 
+    fn get_boosted_yields_factors(&self) -> Option<BoostedYieldsFactors<Self::Api>> {
+        let config_addr = self.config_sc_address().get();
+        let mapper = self.external_boosted_yields_factors(config_addr);
+        if mapper.is_empty() {
+            return None;
+        }
+
+        Some(mapper.get())
+    }
+
+    fn get_energy_factory_address(&self) -> Option<ManagedAddress> {
+        let config_addr = self.config_sc_address().get();
+        let mapper = self.external_energy_factory_address(config_addr);
+        if mapper.is_empty() {
+            return None;
+        }
+
+        Some(mapper.get())
+    }
+
     fn require_not_globally_paused(&self) {
         let config_addr = self.config_sc_address().get();
         let pause_status = self.external_global_pause_status(config_addr).get();
@@ -139,12 +277,66 @@ pub trait ReadConfigModule {
         sc_addr: ManagedAddress,
     ) -> VecMapper<UserRewardTier, ManagedAddress>;
 
+    #[storage_mapper_from_address("scheduledGuildMasterTiers")]
+    fn external_scheduled_guild_master_tiers(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> VecMapper<GuildMasterRewardTier<Self::Api>, ManagedAddress>;
+
+    #[storage_mapper_from_address("scheduledUserTiers")]
+    fn external_scheduled_user_tiers(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> VecMapper<UserRewardTier, ManagedAddress>;
+
+    #[storage_mapper_from_address("scheduledGuildMasterTiersTimestamp")]
+    fn external_scheduled_guild_master_tiers_timestamp(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<Timestamp, ManagedAddress>;
+
+    #[storage_mapper_from_address("scheduledUserTiersTimestamp")]
+    fn external_scheduled_user_tiers_timestamp(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<Timestamp, ManagedAddress>;
+
+    #[storage_mapper_from_address("configCheckpoints")]
+    fn external_config_checkpoints(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> VecMapper<ConfigCheckpoint<Self::Api>, ManagedAddress>;
+
+    #[storage_mapper_from_address("guildMasterRewardCurve")]
+    fn external_guild_master_reward_curve(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<RewardCurve, ManagedAddress>;
+
+    #[storage_mapper_from_address("userRewardCurve")]
+    fn external_user_reward_curve(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<RewardCurve, ManagedAddress>;
+
     #[storage_mapper_from_address("maxStakedTokens")]
     fn external_max_staked_tokens(
         &self,
         sc_addr: ManagedAddress,
     ) -> SingleValueMapper<BigUint, ManagedAddress>;
 
+    #[storage_mapper_from_address("maxGuildMasterCommission")]
+    fn external_max_guild_master_commission(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<Percent, ManagedAddress>;
+
+    #[storage_mapper_from_address("commissionNoticeSeconds")]
+    fn external_commission_notice_seconds(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<Timestamp, ManagedAddress>;
+
     #[storage_mapper_from_address("minUnbondEpochsUser")]
     fn external_min_unbond_epochs_user(
         &self,
@@ -157,6 +349,19 @@ pub trait ReadConfigModule {
         sc_addr: ManagedAddress,
     ) -> SingleValueMapper<Epoch, ManagedAddress>;
 
+    #[storage_mapper_from_address("instantUnbondFee")]
+    fn external_instant_unbond_fee(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<Percent, ManagedAddress>;
+
+    #[storage_mapper_from_address("lockMultiplier")]
+    fn external_lock_multiplier(
+        &self,
+        sc_addr: ManagedAddress,
+        lock_epochs: Epoch,
+    ) -> SingleValueMapper<Percent, ManagedAddress>;
+
     #[storage_mapper_from_address("minStakeUser")]
     fn external_min_stake_user(
         &self,
@@ -169,17 +374,17 @@ pub trait ReadConfigModule {
         sc_addr: ManagedAddress,
     ) -> SingleValueMapper<BigUint, ManagedAddress>;
 
-    #[storage_mapper_from_address("secondsPerBlock")]
-    fn external_seconds_per_block(
+    #[storage_mapper_from_address("perSecondRewardAmount")]
+    fn external_per_second_reward_amount(
         &self,
         sc_addr: ManagedAddress,
-    ) -> SingleValueMapper<u64, ManagedAddress>;
+    ) -> SingleValueMapper<BigUint, ManagedAddress>;
 
-    #[storage_mapper_from_address("perBlockRewardAmount")]
-    fn external_per_block_reward_amount(
+    #[storage_mapper_from_address("emissionSchedule")]
+    fn external_emission_schedule(
         &self,
         sc_addr: ManagedAddress,
-    ) -> SingleValueMapper<BigUint, ManagedAddress>;
+    ) -> SingleValueMapper<EmissionSchedule<Self::Api>, ManagedAddress>;
 
     #[storage_mapper_from_address("totalStakingTokenMinted")]
     fn external_total_staking_token_minted(
@@ -222,4 +427,16 @@ pub trait ReadConfigModule {
         &self,
         sc_addr: ManagedAddress,
     ) -> SingleValueMapper<usize, ManagedAddress>;
+
+    #[storage_mapper_from_address("boostedYieldsFactors")]
+    fn external_boosted_yields_factors(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<BoostedYieldsFactors<Self::Api>, ManagedAddress>;
+
+    #[storage_mapper_from_address("energyFactoryAddress")]
+    fn external_energy_factory_address(
+        &self,
+        sc_addr: ManagedAddress,
+    ) -> SingleValueMapper<ManagedAddress, ManagedAddress>;
 }
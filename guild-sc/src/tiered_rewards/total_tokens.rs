@@ -42,6 +42,7 @@ pub trait TokenPerTierModule: super::read_config::ReadConfigModule {
         if caller != &guild_master {
             let user_tokens_mapper = self.user_tokens(caller);
             self.add_tokens_common(tokens, &user_tokens_mapper);
+            self.count_member(caller, &user_tokens_mapper);
         } else {
             let guild_master_tokens_mapper = self.guild_master_tokens();
             self.add_tokens_common(tokens, &guild_master_tokens_mapper);
@@ -60,12 +61,30 @@ pub trait TokenPerTierModule: super::read_config::ReadConfigModule {
         if caller != &guild_master {
             let user_tokens_mapper = self.user_tokens(caller);
             self.remove_tokens_common(tokens, &user_tokens_mapper);
+            if user_tokens_mapper.is_empty() && self.counted_member(caller).take() {
+                self.member_count().update(|count| *count -= 1);
+            }
         } else {
             let guild_master_tokens_mapper = self.guild_master_tokens();
             self.remove_tokens_common(tokens, &guild_master_tokens_mapper);
         }
     }
 
+    /// Members staked before the count was tracked are counted on their next stake.
+    /// The vault's stake is not a member.
+    fn count_member(&self, user: &ManagedAddress, user_tokens_mapper: &SingleValueMapper<BigUint>) {
+        let counted_member_mapper = self.counted_member(user);
+        if counted_member_mapper.get()
+            || user_tokens_mapper.is_empty()
+            || user == &self.blockchain().get_sc_address()
+        {
+            return;
+        }
+
+        counted_member_mapper.set(true);
+        self.member_count().update(|count| *count += 1);
+    }
+
     #[inline]
     fn remove_tokens_common(&self, tokens: &BigUint, mapper: &SingleValueMapper<BigUint>) {
         mapper.update(|total_tokens| {
@@ -101,4 +120,11 @@ pub trait TokenPerTierModule: super::read_config::ReadConfigModule {
 
     #[storage_mapper("userTokens")]
     fn user_tokens(&self, user: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("countedMember")]
+    fn counted_member(&self, user: &ManagedAddress) -> SingleValueMapper<bool>;
+
+    #[view(getMemberCount)]
+    #[storage_mapper("memberCount")]
+    fn member_count(&self) -> SingleValueMapper<usize>;
 }
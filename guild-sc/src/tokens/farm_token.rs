@@ -43,6 +43,19 @@ pub trait FarmTokenModule:
         );
     }
 
+    /// Lets users send farm tokens to the factory's router
+    #[endpoint(setTransferRoleFarmTokenFactory)]
+    fn set_transfer_role_farm_token_factory(&self) {
+        self.require_caller_has_owner_or_admin_permissions();
+
+        let factory_address = self.blockchain().get_owner_address();
+        self.farm_token().set_local_roles_for_address(
+            &factory_address,
+            &[EsdtLocalRole::Transfer],
+            None,
+        );
+    }
+
     #[callback]
     fn t_role_farm_token_callback(&self, #[call_result] result: ManagedAsyncCallResult<()>) {
         if let ManagedAsyncCallResult::Ok(()) = result {
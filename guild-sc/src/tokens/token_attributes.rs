@@ -1,10 +1,11 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
-use common_structs::Epoch;
+use common_structs::{Epoch, Percent};
 use fixed_supply_token::FixedSupplyToken;
 use math::weighted_average_round_up;
 use mergeable::Mergeable;
+use multiversx_sc::codec::{DecodeErrorHandler, NestedDecodeInput, TopDecodeInput};
 
 pub trait LocalFarmToken<M: ManagedTypeApi> {
     fn get_reward_per_share(&self) -> BigUint<M>;
@@ -24,13 +25,74 @@ pub trait LocalFarmToken<M: ManagedTypeApi> {
     NestedDecode,
     TypeAbi,
     Clone,
+    Copy,
     PartialEq,
     Debug,
 )]
+pub struct PositionLock {
+    pub unlock_epoch: Epoch,
+    pub multiplier: Percent,
+}
+
+impl PositionLock {
+    #[inline]
+    pub fn is_active(&self, current_epoch: Epoch) -> bool {
+        current_epoch < self.unlock_epoch
+    }
+}
+
+#[derive(ManagedVecItem, TopEncode, NestedEncode, TypeAbi, Clone, PartialEq, Debug)]
 pub struct StakingFarmTokenAttributes<M: ManagedTypeApi> {
     pub reward_per_share: BigUint<M>,
     pub compounded_reward: BigUint<M>,
     pub current_farm_amount: BigUint<M>,
+    pub lock: Option<PositionLock>,
+}
+
+impl<M: ManagedTypeApi> StakingFarmTokenAttributes<M> {
+    pub fn get_active_lock(&self, current_epoch: Epoch) -> Option<PositionLock> {
+        self.lock.filter(|lock| lock.is_active(current_epoch))
+    }
+
+    pub fn clear_expired_lock(&mut self, current_epoch: Epoch) {
+        self.lock = self.get_active_lock(current_epoch);
+    }
+}
+
+impl<M: ManagedTypeApi> TopDecode for StakingFarmTokenAttributes<M> {
+    fn top_decode_or_handle_err<I, H>(input: I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: TopDecodeInput,
+        H: DecodeErrorHandler,
+    {
+        multiversx_sc::codec::top_decode_from_nested_or_handle_err(input, h)
+    }
+}
+
+impl<M: ManagedTypeApi> NestedDecode for StakingFarmTokenAttributes<M> {
+    fn dep_decode_or_handle_err<I, H>(input: &mut I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: NestedDecodeInput,
+        H: DecodeErrorHandler,
+    {
+        let reward_per_share = BigUint::dep_decode_or_handle_err(input, h)?;
+        let compounded_reward = BigUint::dep_decode_or_handle_err(input, h)?;
+        let current_farm_amount = BigUint::dep_decode_or_handle_err(input, h)?;
+
+        // tokens created before position locks have no lock field
+        let lock = if input.is_depleted() {
+            None
+        } else {
+            Option::<PositionLock>::dep_decode_or_handle_err(input, h)?
+        };
+
+        Ok(StakingFarmTokenAttributes {
+            reward_per_share,
+            compounded_reward,
+            current_farm_amount,
+            lock,
+        })
+    }
 }
 
 #[derive(ManagedVecItem, Clone)]
@@ -78,14 +140,16 @@ impl<M: ManagedTypeApi> FixedSupplyToken<M> for StakingFarmTokenAttributes<M> {
             reward_per_share: self.reward_per_share,
             compounded_reward: new_compounded_reward,
             current_farm_amount: new_current_farm_amount,
+            lock: self.lock,
         }
     }
 }
 
 impl<M: ManagedTypeApi> Mergeable<M> for StakingFarmTokenAttributes<M> {
+    /// Only positions with the same lock are merged
     #[inline]
-    fn can_merge_with(&self, _other: &Self) -> bool {
-        true
+    fn can_merge_with(&self, other: &Self) -> bool {
+        self.lock == other.lock
     }
 
     fn merge_with(&mut self, other: Self) {
@@ -135,3 +199,25 @@ impl<M: ManagedTypeApi> FixedSupplyToken<M> for UnbondSftAttributes<M> {
         }
     }
 }
+
+impl<M: ManagedTypeApi> Mergeable<M> for UnbondSftAttributes<M> {
+    /// Tokens that may cancel unbond only merge with each other
+    #[inline]
+    fn can_merge_with(&self, other: &Self) -> bool {
+        self.opt_original_attributes.is_some() == other.opt_original_attributes.is_some()
+    }
+
+    fn merge_with(&mut self, other: Self) {
+        self.error_if_not_mergeable(&other);
+
+        self.unlock_epoch = core::cmp::max(self.unlock_epoch, other.unlock_epoch);
+        self.supply += other.supply;
+
+        if let (Some(original_attributes), Some(other_original_attributes)) = (
+            self.opt_original_attributes.as_mut(),
+            other.opt_original_attributes,
+        ) {
+            original_attributes.merge_with(other_original_attributes);
+        }
+    }
+}
@@ -1,9 +1,18 @@
 multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
 
+use common_structs::PaymentsVec;
 use farm::base_functions::ClaimRewardsResultType;
 
 use crate::farm_base_impl::base_traits_impl::FarmStakingWrapper;
 
+/// Where the claimed rewards go. The new farm token always goes back to the caller.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub enum ClaimDestination<M: ManagedTypeApi> {
+    Address(ManagedAddress<M>),
+    Guild(ManagedAddress<M>),
+}
+
 #[multiversx_sc::module]
 pub trait ClaimStakeFarmRewardsModule:
     crate::custom_rewards::CustomRewardsModule
@@ -22,26 +31,68 @@ pub trait ClaimStakeFarmRewardsModule:
     + crate::tiered_rewards::read_config::ReadConfigModule
     + crate::tiered_rewards::total_tokens::TokenPerTierModule
     + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + super::referrals::ReferralsModule
 {
     #[payable("*")]
     #[endpoint(claimRewards)]
-    fn claim_rewards(&self) -> ClaimRewardsResultType<Self::Api> {
+    fn claim_rewards(
+        &self,
+        opt_destination: OptionalValue<ClaimDestination<Self::Api>>,
+    ) -> ClaimRewardsResultType<Self::Api> {
         self.require_not_closing();
         self.require_not_globally_paused();
 
+        if let OptionalValue::Some(ClaimDestination::Guild(_)) = &opt_destination {
+            require!(
+                self.reward_token_id().get() == self.farming_token_id().get(),
+                "Rewards may only be staked if paid in the farming token"
+            );
+        }
+
         let caller = self.blockchain().get_caller();
         let payments = self.get_non_empty_payments();
-        let claim_result =
-            self.claim_rewards_base::<FarmStakingWrapper<Self>>(caller.clone(), payments);
+        let (new_farm_token, base_rewards_payment) = self.claim_rewards_common(&caller, payments);
+        self.send_payment_non_zero(&caller, &new_farm_token);
 
-        let reward_token_id = self.reward_token_id().get();
-        let base_rewards_payment = EsdtTokenPayment::new(reward_token_id, 0, claim_result.rewards);
+        match opt_destination {
+            OptionalValue::Some(ClaimDestination::Address(address)) => {
+                self.send_payment_non_zero(&address, &base_rewards_payment);
+            }
+            OptionalValue::Some(ClaimDestination::Guild(guild)) => {
+                self.stake_rewards_in_guild(guild, &caller, &base_rewards_payment);
+            }
+            OptionalValue::None => {
+                self.send_payment_non_zero(&caller, &base_rewards_payment);
+            }
+        }
 
-        self.send_payment_non_zero(&caller, &claim_result.new_farm_token.payment);
-        self.send_payment_non_zero(&caller, &base_rewards_payment);
+        (new_farm_token, base_rewards_payment).into()
+    }
+
+    /// Returns the new farm token and the rewards, without sending them
+    fn claim_rewards_common(
+        &self,
+        user: &ManagedAddress,
+        payments: PaymentsVec<Self::Api>,
+    ) -> (EsdtTokenPayment, EsdtTokenPayment) {
+        let mut claim_result =
+            self.claim_rewards_base::<FarmStakingWrapper<Self>>(user.clone(), payments);
+        self.accrue_referral_rewards(user, &claim_result.rewards, &mut claim_result.storage_cache);
+
+        let accrued_boosted_rewards = self.claim_boosted_yields_rewards(user);
+        let boosted_rewards = self.take_payable_rewards(
+            user,
+            accrued_boosted_rewards,
+            &mut claim_result.storage_cache.reward_reserve,
+        );
+
+        let total_rewards = claim_result.rewards + boosted_rewards;
+        let reward_token_id = self.reward_token_id().get();
+        let base_rewards_payment = EsdtTokenPayment::new(reward_token_id, 0, total_rewards);
 
         self.emit_claim_rewards_event(
-            &caller,
+            user,
             claim_result.context,
             claim_result.new_farm_token.clone(),
             base_rewards_payment.clone(),
@@ -49,6 +100,25 @@ pub trait ClaimStakeFarmRewardsModule:
             claim_result.storage_cache,
         );
 
-        (claim_result.new_farm_token.payment, base_rewards_payment).into()
+        (claim_result.new_farm_token.payment, base_rewards_payment)
+    }
+
+    /// The rewards go through the factory, which stakes them in the target guild for the user
+    fn stake_rewards_in_guild(
+        &self,
+        guild: ManagedAddress,
+        user: &ManagedAddress,
+        rewards_payment: &EsdtTokenPayment,
+    ) {
+        if rewards_payment.amount == 0 {
+            return;
+        }
+
+        let guild_factory = self.blockchain().get_owner_address();
+        let _: IgnoreValue = self
+            .guild_factory_proxy(guild_factory)
+            .stake_rewards_in_guild(guild, user.clone())
+            .with_esdt_transfer(rewards_payment.clone())
+            .execute_on_dest_context();
     }
 }
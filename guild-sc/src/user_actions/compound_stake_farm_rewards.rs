@@ -1,3 +1,5 @@
+use common_structs::PaymentsVec;
+
 use crate::farm_base_impl::base_traits_impl::FarmStakingWrapper;
 
 multiversx_sc::imports!();
@@ -22,26 +24,55 @@ pub trait CompoundStakeFarmRewardsModule:
     + crate::tiered_rewards::call_config::CallConfigModule
     + super::close_guild::CloseGuildModule
     + crate::farm_base_impl::claim_rewards::BaseClaimRewardsModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + super::referrals::ReferralsModule
 {
     #[payable("*")]
     #[endpoint(compoundRewards)]
     fn compound_rewards(&self) -> EsdtTokenPayment {
         self.require_not_closing();
         self.require_not_globally_paused();
+        self.require_compounding_enabled();
 
         let caller = self.blockchain().get_caller();
         let payments = self.get_non_empty_payments();
-        let compound_result =
-            self.compound_rewards_base::<FarmStakingWrapper<Self>>(caller.clone(), payments);
+        let new_farm_token = self.compound_rewards_common(&caller, payments);
+        self.send_payment_non_zero(&caller, &new_farm_token);
+
+        new_farm_token
+    }
+
+    /// Returns the new farm token, without sending it
+    fn compound_rewards_common(
+        &self,
+        user: &ManagedAddress,
+        payments: PaymentsVec<Self::Api>,
+    ) -> EsdtTokenPayment {
+        let mut compound_result =
+            self.compound_rewards_base::<FarmStakingWrapper<Self>>(user.clone(), payments);
+        self.accrue_referral_rewards(
+            user,
+            &compound_result.compounded_rewards,
+            &mut compound_result.storage_cache,
+        );
 
         let new_farm_token = compound_result.new_farm_token.payment.clone();
-        self.send_payment_non_zero(&caller, &new_farm_token);
 
-        self.add_tokens(&caller, &compound_result.compounded_rewards);
+        let current_epoch = self.blockchain().get_block_epoch();
+        if let Some(lock) = compound_result
+            .new_farm_token
+            .attributes
+            .get_active_lock(current_epoch)
+        {
+            self.add_position_lock_weight(&compound_result.compounded_rewards, &lock);
+        }
+
+        self.add_tokens(user, &compound_result.compounded_rewards);
         self.call_increase_total_staked_tokens(compound_result.compounded_rewards.clone());
+        self.update_boosted_yields_for_user(user);
 
         self.emit_compound_rewards_event(
-            &caller,
+            user,
             compound_result.context,
             compound_result.new_farm_token,
             compound_result.compounded_rewards,
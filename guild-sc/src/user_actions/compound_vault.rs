@@ -0,0 +1,265 @@
+multiversx_sc::imports!();
+
+use common_structs::Percent;
+
+use crate::{
+    contexts::storage_cache::StorageCache,
+    custom_rewards::MAX_PERCENT,
+    farm_base_impl::base_traits_impl::{FarmContract, FarmStakingWrapper},
+};
+
+pub const MAX_VAULT_COMPOUND_BOUNTY: Percent = 500;
+
+/// Positions deposited in the vault are held by the guild and compounded together.
+/// Depositors receive vault shares instead of farm tokens.
+#[multiversx_sc::module]
+pub trait CompoundVaultModule:
+    crate::custom_rewards::CustomRewardsModule
+    + crate::rewards::RewardsModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + crate::tokens::request_id::RequestIdModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + crate::farm_base_impl::base_farm_validation::BaseFarmValidationModule
+    + crate::farm_base_impl::claim_rewards::BaseClaimRewardsModule
+    + utils::UtilsModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+    + crate::tiered_rewards::call_config::CallConfigModule
+    + super::custom_events::CustomEventsModule
+    + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + super::referrals::ReferralsModule
+{
+    /// Percentage of the compounded rewards paid to the caller of compoundVault
+    #[endpoint(setVaultCompoundBounty)]
+    fn set_vault_compound_bounty(&self, percentage: Percent) {
+        self.require_caller_has_admin_permissions();
+        require!(
+            percentage <= MAX_VAULT_COMPOUND_BOUNTY,
+            "Invalid percentage"
+        );
+
+        self.vault_compound_bounty().set(percentage);
+    }
+
+    /// The rewards of the deposited positions are compounded into the vault as well.
+    /// Returns the received shares.
+    #[payable("*")]
+    #[endpoint(depositToVault)]
+    fn deposit_to_vault(&self) -> BigUint {
+        self.require_not_closing();
+        self.require_not_globally_paused();
+        self.require_compounding_enabled();
+
+        let caller = self.blockchain().get_caller();
+        let guild_master = self.guild_master_address().get();
+        require!(caller != guild_master, "Guild master may not use the vault");
+
+        let payments = self.get_non_empty_payments();
+        let mut claim_result = self
+            .claim_rewards_base_no_farm_token_mint::<FarmStakingWrapper<Self>>(
+                caller.clone(),
+                payments,
+            );
+        require!(
+            claim_result.new_farm_token.attributes.lock.is_none(),
+            "Locked positions may not use the vault"
+        );
+        self.accrue_referral_rewards(
+            &caller,
+            &claim_result.rewards,
+            &mut claim_result.storage_cache,
+        );
+        self.compound_vault(&mut claim_result.storage_cache, 0);
+
+        let position_amount = claim_result.new_farm_token.payment.amount;
+        let compounded_rewards = claim_result.rewards;
+        let deposit_amount = &position_amount + &compounded_rewards;
+        claim_result.storage_cache.farm_token_supply += &compounded_rewards;
+        let deposit_compounded_rewards =
+            &claim_result.new_farm_token.attributes.compounded_reward + &compounded_rewards;
+        self.vault_compounded_rewards()
+            .update(|compounded| *compounded += deposit_compounded_rewards);
+
+        let vault_amount_mapper = self.vault_farm_amount();
+        let total_shares_mapper = self.total_vault_shares();
+        let vault_amount = vault_amount_mapper.get();
+        let total_shares = total_shares_mapper.get();
+        let new_shares = if total_shares == 0 || vault_amount == 0 {
+            deposit_amount.clone()
+        } else {
+            &deposit_amount * &total_shares / &vault_amount
+        };
+        require!(new_shares > 0, "Deposit too small");
+
+        vault_amount_mapper.set(vault_amount + &deposit_amount);
+        total_shares_mapper.set(total_shares + &new_shares);
+        self.vault_shares(&caller)
+            .update(|shares| *shares += &new_shares);
+
+        let sc_address = self.blockchain().get_sc_address();
+        self.remove_tokens(&caller, &position_amount);
+        self.add_tokens(&sc_address, &deposit_amount);
+        self.require_over_min_stake(&caller);
+        self.update_boosted_yields_for_user(&caller);
+
+        if compounded_rewards > 0 {
+            self.call_increase_total_staked_tokens(compounded_rewards);
+        }
+
+        self.emit_vault_deposit_event(&caller, deposit_amount, new_shares.clone());
+
+        new_shares
+    }
+
+    /// Returns a new farm token for the value of the given shares
+    #[endpoint(withdrawFromVault)]
+    fn withdraw_from_vault(&self, shares: BigUint) -> EsdtTokenPayment {
+        self.require_not_globally_paused();
+
+        let caller = self.blockchain().get_caller();
+        let user_shares_mapper = self.vault_shares(&caller);
+        let user_shares = user_shares_mapper.get();
+        require!(shares > 0 && shares <= user_shares, "Invalid shares amount");
+
+        let mut storage_cache = StorageCache::new(self);
+        self.validate_contract_state(storage_cache.contract_state, &storage_cache.farm_token_id);
+        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+        self.compound_vault(&mut storage_cache, 0);
+
+        let vault_amount_mapper = self.vault_farm_amount();
+        let total_shares_mapper = self.total_vault_shares();
+        let vault_amount = vault_amount_mapper.get();
+        let total_shares = total_shares_mapper.get();
+        let farm_amount = &shares * &vault_amount / &total_shares;
+        require!(farm_amount > 0, "Withdraw too small");
+
+        // the compounded share was never added to the base staked tokens
+        let vault_compounded_mapper = self.vault_compounded_rewards();
+        let vault_compounded = vault_compounded_mapper.get();
+        let compounded_reward = &vault_compounded * &farm_amount / &vault_amount;
+        vault_compounded_mapper.set(&vault_compounded - &compounded_reward);
+
+        vault_amount_mapper.set(&vault_amount - &farm_amount);
+        total_shares_mapper.set(&total_shares - &shares);
+        user_shares_mapper.set(&user_shares - &shares);
+
+        let sc_address = self.blockchain().get_sc_address();
+        self.remove_tokens(&sc_address, &farm_amount);
+        self.add_tokens(&caller, &farm_amount);
+        self.require_over_min_stake(&caller);
+        self.update_boosted_yields_for_user(&caller);
+
+        let mut attributes = FarmStakingWrapper::<Self>::create_enter_farm_initial_attributes(
+            farm_amount.clone(),
+            storage_cache.user_rps.clone(),
+        );
+        attributes.compounded_reward = compounded_reward;
+        let new_farm_token = self.farm_token().nft_create(farm_amount, &attributes);
+        self.send_payment_non_zero(&caller, &new_farm_token);
+
+        self.emit_vault_withdraw_event(&caller, new_farm_token.amount.clone(), shares);
+
+        new_farm_token
+    }
+
+    /// Anyone may compound the vault, receiving the bounty out of the compounded rewards
+    #[endpoint(compoundVault)]
+    fn compound_vault_endpoint(&self) -> EsdtTokenPayment {
+        self.require_not_closing();
+        self.require_not_globally_paused();
+        self.require_compounding_enabled();
+
+        let mut storage_cache = StorageCache::new(self);
+        self.validate_contract_state(storage_cache.contract_state, &storage_cache.farm_token_id);
+        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+
+        let bounty_percentage = self.vault_compound_bounty().get();
+        let bounty = self.compound_vault(&mut storage_cache, bounty_percentage);
+        let bounty_payment =
+            EsdtTokenPayment::new(storage_cache.reward_token_id.clone(), 0, bounty);
+
+        let caller = self.blockchain().get_caller();
+        self.send_payment_non_zero(&caller, &bounty_payment);
+
+        self.emit_vault_compound_event(&caller, bounty_payment.amount.clone());
+
+        bounty_payment
+    }
+
+    /// Compounds the vault rewards since the last compounding. Returns the bounty.
+    fn compound_vault(
+        &self,
+        storage_cache: &mut StorageCache<Self>,
+        bounty_percentage: Percent,
+    ) -> BigUint {
+        let vault_rps_mapper = self.vault_reward_per_share();
+        let vault_rps = vault_rps_mapper.get();
+        vault_rps_mapper.set(&storage_cache.user_rps);
+
+        let vault_amount_mapper = self.vault_farm_amount();
+        let vault_amount = vault_amount_mapper.get();
+        if vault_amount == 0 || storage_cache.user_rps <= vault_rps {
+            return BigUint::zero();
+        }
+
+        let rps_diff = &storage_cache.user_rps - &vault_rps;
+        let accrued_rewards = &vault_amount * &rps_diff / &storage_cache.division_safety_constant;
+        let sc_address = self.blockchain().get_sc_address();
+        let rewards = self.take_payable_rewards(
+            &sc_address,
+            accrued_rewards,
+            &mut storage_cache.reward_reserve,
+        );
+        let bounty = &rewards * bounty_percentage / MAX_PERCENT;
+        let compounded_rewards = &rewards - &bounty;
+
+        storage_cache.farm_token_supply += &compounded_rewards;
+        vault_amount_mapper.set(vault_amount + &compounded_rewards);
+        self.vault_compounded_rewards()
+            .update(|compounded| *compounded += &compounded_rewards);
+
+        self.add_tokens(&sc_address, &compounded_rewards);
+        if compounded_rewards > 0 {
+            self.call_increase_total_staked_tokens(compounded_rewards);
+        }
+
+        bounty
+    }
+
+    #[view(getVaultBalance)]
+    fn get_vault_balance(&self, user: ManagedAddress) -> BigUint {
+        let total_shares = self.total_vault_shares().get();
+        if total_shares == 0 {
+            return BigUint::zero();
+        }
+
+        self.vault_shares(&user).get() * self.vault_farm_amount().get() / total_shares
+    }
+
+    #[view(getVaultCompoundBounty)]
+    #[storage_mapper("vaultCompoundBounty")]
+    fn vault_compound_bounty(&self) -> SingleValueMapper<Percent>;
+
+    #[view(getVaultShares)]
+    #[storage_mapper("vaultShares")]
+    fn vault_shares(&self, user: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalVaultShares)]
+    #[storage_mapper("totalVaultShares")]
+    fn total_vault_shares(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getVaultFarmAmount)]
+    #[storage_mapper("vaultFarmAmount")]
+    fn vault_farm_amount(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("vaultCompoundedRewards")]
+    fn vault_compounded_rewards(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("vaultRewardPerShare")]
+    fn vault_reward_per_share(&self) -> SingleValueMapper<BigUint>;
+}
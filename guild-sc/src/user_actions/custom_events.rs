@@ -17,6 +17,18 @@ pub struct MigrateToOtherFarmData<M: ManagedTypeApi> {
     pub base_rewards: EsdtTokenPayment<M>,
 }
 
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct TransferPositionEventData<M: ManagedTypeApi> {
+    pub to: ManagedAddress<M>,
+    pub payments: ManagedVec<M, EsdtTokenPayment<M>>,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct VaultEventData<M: ManagedTypeApi> {
+    pub farm_amount: BigUint<M>,
+    pub shares: BigUint<M>,
+}
+
 #[multiversx_sc::module]
 pub trait CustomEventsModule {
     fn emit_cancel_unbond_event(
@@ -58,6 +70,59 @@ pub trait CustomEventsModule {
         self.migrate_to_other_farm_event(caller, &event_data);
     }
 
+    fn emit_transfer_position_event(
+        &self,
+        caller: &ManagedAddress,
+        to: &ManagedAddress,
+        payments: ManagedVec<EsdtTokenPayment>,
+    ) {
+        let event_data = TransferPositionEventData {
+            to: to.clone(),
+            payments,
+        };
+        self.transfer_position_event(caller, &event_data);
+    }
+
+    #[inline]
+    fn emit_guild_master_changed_event(
+        &self,
+        old_guild_master: &ManagedAddress,
+        new_guild_master: &ManagedAddress,
+    ) {
+        self.guild_master_changed_event(old_guild_master, new_guild_master);
+    }
+
+    fn emit_vault_deposit_event(
+        &self,
+        caller: &ManagedAddress,
+        farm_amount: BigUint,
+        shares: BigUint,
+    ) {
+        let event_data = VaultEventData {
+            farm_amount,
+            shares,
+        };
+        self.vault_deposit_event(caller, &event_data);
+    }
+
+    fn emit_vault_withdraw_event(
+        &self,
+        caller: &ManagedAddress,
+        farm_amount: BigUint,
+        shares: BigUint,
+    ) {
+        let event_data = VaultEventData {
+            farm_amount,
+            shares,
+        };
+        self.vault_withdraw_event(caller, &event_data);
+    }
+
+    #[inline]
+    fn emit_vault_compound_event(&self, caller: &ManagedAddress, bounty: BigUint) {
+        self.vault_compound_event(caller, &bounty);
+    }
+
     #[event("cancelUnbondEvent")]
     fn cancel_unbond_event(
         &self,
@@ -78,4 +143,35 @@ pub trait CustomEventsModule {
         #[indexed] caller: &ManagedAddress,
         event_data: &MigrateToOtherFarmData<Self::Api>,
     );
+
+    #[event("transferPositionEvent")]
+    fn transfer_position_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        event_data: &TransferPositionEventData<Self::Api>,
+    );
+
+    #[event("guildMasterChangedEvent")]
+    fn guild_master_changed_event(
+        &self,
+        #[indexed] old_guild_master: &ManagedAddress,
+        #[indexed] new_guild_master: &ManagedAddress,
+    );
+
+    #[event("vaultDepositEvent")]
+    fn vault_deposit_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        event_data: &VaultEventData<Self::Api>,
+    );
+
+    #[event("vaultWithdrawEvent")]
+    fn vault_withdraw_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        event_data: &VaultEventData<Self::Api>,
+    );
+
+    #[event("vaultCompoundEvent")]
+    fn vault_compound_event(&self, #[indexed] caller: &ManagedAddress, bounty: &BigUint);
 }
@@ -0,0 +1,187 @@
+use common_structs::PaymentsVec;
+use mergeable::Mergeable;
+
+use crate::tokens::token_attributes::StakingFarmTokenAttributes;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+static NO_DELEGATED_POSITIONS_ERR_MSG: &[u8] = b"No delegated positions";
+
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, Copy, PartialEq, Debug,
+)]
+pub enum DelegateAction {
+    Stake,
+    Claim,
+    Compound,
+}
+
+/// Users may approve delegates, like keeper bots or custody contracts, to act on their behalf.
+/// Farm tokens may only be transferred by the guild, so delegates claim and compound
+/// the positions users deposit in the guild. The outputs always go to the user.
+#[multiversx_sc::module]
+pub trait DelegatesModule:
+    super::claim_stake_farm_rewards::ClaimStakeFarmRewardsModule
+    + super::compound_stake_farm_rewards::CompoundStakeFarmRewardsModule
+{
+    /// Replaces the actions the delegate was previously approved for
+    #[endpoint(approveDelegate)]
+    fn approve_delegate(
+        &self,
+        delegate: ManagedAddress,
+        actions: MultiValueEncoded<DelegateAction>,
+    ) {
+        require!(!actions.is_empty(), "No actions");
+
+        let caller = self.blockchain().get_caller();
+        require!(caller != delegate, "Invalid delegate");
+
+        let mut actions_mapper = self.delegate_actions(&caller, &delegate);
+        actions_mapper.clear();
+        for action in actions {
+            let _ = actions_mapper.insert(action);
+        }
+
+        let _ = self.user_delegates(&caller).insert(delegate);
+    }
+
+    #[endpoint(revokeDelegate)]
+    fn revoke_delegate(&self, delegate: ManagedAddress) {
+        let caller = self.blockchain().get_caller();
+        let removed = self.user_delegates(&caller).swap_remove(&delegate);
+        require!(removed, "Unknown delegate");
+
+        self.delegate_actions(&caller, &delegate).clear();
+    }
+
+    /// The guild holds the positions until they are withdrawn
+    #[payable("*")]
+    #[endpoint(depositDelegatedPositions)]
+    fn deposit_delegated_positions(&self) {
+        let caller = self.blockchain().get_caller();
+        let payments = self.get_non_empty_payments();
+        self.farm_token().require_all_same_token(&payments);
+
+        self.delegated_positions(&caller).update(|positions| {
+            for payment in &payments {
+                positions.push(payment);
+            }
+
+            self.require_same_position_lock(positions);
+        });
+    }
+
+    #[endpoint(withdrawDelegatedPositions)]
+    fn withdraw_delegated_positions(&self) -> PaymentsVec<Self::Api> {
+        let caller = self.blockchain().get_caller();
+        let positions = self.delegated_positions(&caller).take();
+        require!(!positions.is_empty(), NO_DELEGATED_POSITIONS_ERR_MSG);
+
+        self.send().direct_multi(&caller, &positions);
+
+        positions
+    }
+
+    /// The rewards are sent to the user, while the new position stays in the guild
+    #[endpoint(claimRewardsFor)]
+    fn claim_rewards_for(&self, user: ManagedAddress) -> EsdtTokenPayment {
+        self.require_not_closing();
+        self.require_not_globally_paused();
+
+        let positions = self.take_delegated_positions(&user, DelegateAction::Claim);
+        let (new_farm_token, base_rewards_payment) = self.claim_rewards_common(&user, positions);
+        self.send_payment_non_zero(&user, &base_rewards_payment);
+        self.delegated_positions(&user)
+            .set(ManagedVec::from_single_item(new_farm_token));
+
+        base_rewards_payment
+    }
+
+    #[endpoint(compoundRewardsFor)]
+    fn compound_rewards_for(&self, user: ManagedAddress) -> EsdtTokenPayment {
+        self.require_not_closing();
+        self.require_not_globally_paused();
+        self.require_compounding_enabled();
+
+        let positions = self.take_delegated_positions(&user, DelegateAction::Compound);
+        let new_farm_token = self.compound_rewards_common(&user, positions);
+        self.delegated_positions(&user)
+            .set(ManagedVec::from_single_item(new_farm_token.clone()));
+
+        new_farm_token
+    }
+
+    #[view(getDelegateActions)]
+    fn get_delegate_actions(
+        &self,
+        user: ManagedAddress,
+        delegate: ManagedAddress,
+    ) -> MultiValueEncoded<DelegateAction> {
+        let mut result = MultiValueEncoded::new();
+        for action in self.delegate_actions(&user, &delegate).iter() {
+            result.push(action);
+        }
+
+        result
+    }
+
+    fn take_delegated_positions(
+        &self,
+        user: &ManagedAddress,
+        action: DelegateAction,
+    ) -> PaymentsVec<Self::Api> {
+        let caller = self.blockchain().get_caller();
+        self.require_approved_delegate(user, &caller, action);
+
+        let positions = self.delegated_positions(user).take();
+        require!(!positions.is_empty(), NO_DELEGATED_POSITIONS_ERR_MSG);
+
+        positions
+    }
+
+    /// The positions are claimed together, so they must be mergeable
+    fn require_same_position_lock(&self, positions: &PaymentsVec<Self::Api>) {
+        let farm_token_mapper = self.farm_token();
+        let first_attributes: StakingFarmTokenAttributes<Self::Api> =
+            farm_token_mapper.get_token_attributes(positions.get(0).token_nonce);
+        for position in positions.iter().skip(1) {
+            let attributes: StakingFarmTokenAttributes<Self::Api> =
+                farm_token_mapper.get_token_attributes(position.token_nonce);
+            require!(
+                first_attributes.can_merge_with(&attributes),
+                "Positions with different locks"
+            );
+        }
+    }
+
+    fn require_approved_delegate(
+        &self,
+        user: &ManagedAddress,
+        delegate: &ManagedAddress,
+        action: DelegateAction,
+    ) {
+        require!(
+            self.delegate_actions(user, delegate).contains(&action),
+            "Not an approved delegate"
+        );
+    }
+
+    #[view(getUserDelegates)]
+    #[storage_mapper("userDelegates")]
+    fn user_delegates(&self, user: &ManagedAddress) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[storage_mapper("delegateActions")]
+    fn delegate_actions(
+        &self,
+        user: &ManagedAddress,
+        delegate: &ManagedAddress,
+    ) -> UnorderedSetMapper<DelegateAction>;
+
+    #[view(getDelegatedPositions)]
+    #[storage_mapper("delegatedPositions")]
+    fn delegated_positions(
+        &self,
+        user: &ManagedAddress,
+    ) -> SingleValueMapper<PaymentsVec<Self::Api>>;
+}
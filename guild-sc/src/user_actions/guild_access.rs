@@ -0,0 +1,152 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use guild_sc_config::checkpoints::Timestamp;
+
+#[derive(TypeAbi, TopEncode, TopDecode, Clone, Copy, PartialEq, Debug)]
+pub enum GuildAccessMode {
+    Open,
+    Allowlist,
+    Invite,
+}
+
+/// Guild masters control who may join their guild, and how many members it may have.
+/// Existing members may always add to their stake.
+#[multiversx_sc::module]
+pub trait GuildAccessModule:
+    crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+{
+    #[endpoint(setGuildAccessMode)]
+    fn set_guild_access_mode(&self, access_mode: GuildAccessMode) {
+        self.require_caller_guild_master();
+
+        self.guild_access_mode().set(access_mode);
+    }
+
+    #[endpoint(addToGuildAllowlist)]
+    fn add_to_guild_allowlist(&self, users: MultiValueEncoded<ManagedAddress>) {
+        self.require_caller_guild_master();
+
+        let mut allowlist_mapper = self.guild_allowlist();
+        for user in users {
+            let _ = allowlist_mapper.insert(user);
+        }
+    }
+
+    #[endpoint(removeFromGuildAllowlist)]
+    fn remove_from_guild_allowlist(&self, users: MultiValueEncoded<ManagedAddress>) {
+        self.require_caller_guild_master();
+
+        let mut allowlist_mapper = self.guild_allowlist();
+        for user in users {
+            let _ = allowlist_mapper.swap_remove(&user);
+        }
+    }
+
+    /// Zero means no limit
+    #[endpoint(setMaxGuildMembers)]
+    fn set_max_guild_members(&self, max_members: usize) {
+        self.require_caller_guild_master();
+
+        self.max_guild_members().set(max_members);
+    }
+
+    /// Revoked invites may not be accepted anymore
+    #[endpoint(revokeGuildInvites)]
+    fn revoke_guild_invites(&self, signatures: MultiValueEncoded<ManagedBuffer>) {
+        self.require_caller_guild_master();
+
+        let mut revoked_invites_mapper = self.revoked_guild_invites();
+        for signature in signatures {
+            let _ = revoked_invites_mapper.insert(signature);
+        }
+    }
+
+    /// The invite is the guild master's signature of the guild address, the invited user's address,
+    /// the expiry timestamp and the user's invite nonce. Each invite may only be used once.
+    #[endpoint(acceptGuildInvite)]
+    fn accept_guild_invite(&self, expiry_timestamp: Timestamp, signature: ManagedBuffer) {
+        require!(
+            self.guild_access_mode().get() == GuildAccessMode::Invite,
+            "Guild does not use invites"
+        );
+        require!(
+            self.blockchain().get_block_timestamp() <= expiry_timestamp,
+            "Invite expired"
+        );
+        require!(
+            !self.revoked_guild_invites().contains(&signature),
+            "Invite revoked"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let invite_nonce_mapper = self.guild_invite_nonce(&caller);
+        let invite_nonce = invite_nonce_mapper.get();
+        let mut message = self
+            .blockchain()
+            .get_sc_address()
+            .as_managed_buffer()
+            .clone();
+        message.append(caller.as_managed_buffer());
+        message.append_bytes(&expiry_timestamp.to_be_bytes());
+        message.append_bytes(&invite_nonce.to_be_bytes());
+
+        let guild_master = self.guild_master_address().get();
+        self.crypto()
+            .verify_ed25519(guild_master.as_managed_buffer(), &message, &signature);
+
+        invite_nonce_mapper.set(invite_nonce + 1);
+        let _ = self.guild_allowlist().insert(caller);
+    }
+
+    fn require_can_join_guild(&self, user: &ManagedAddress) {
+        let guild_master = self.guild_master_address().get();
+        if user == &guild_master || !self.user_tokens(user).is_empty() {
+            return;
+        }
+
+        if self.guild_access_mode().get() != GuildAccessMode::Open {
+            require!(
+                self.guild_allowlist().contains(user),
+                "Not allowed to join guild"
+            );
+        }
+
+        let max_members = self.max_guild_members().get();
+        require!(
+            max_members == 0 || self.member_count().get() < max_members,
+            "Guild is full"
+        );
+    }
+
+    fn require_caller_guild_master(&self) {
+        let guild_master = self.guild_master_address().get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == guild_master,
+            "Only guild master may manage guild access"
+        );
+    }
+
+    #[view(getGuildAccessMode)]
+    #[storage_mapper("guildAccessMode")]
+    fn guild_access_mode(&self) -> SingleValueMapper<GuildAccessMode>;
+
+    #[view(getGuildAllowlist)]
+    #[storage_mapper("guildAllowlist")]
+    fn guild_allowlist(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// The nonce the next invite of the user must be signed with
+    #[view(getGuildInviteNonce)]
+    #[storage_mapper("guildInviteNonce")]
+    fn guild_invite_nonce(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[view(getRevokedGuildInvites)]
+    #[storage_mapper("revokedGuildInvites")]
+    fn revoked_guild_invites(&self) -> UnorderedSetMapper<ManagedBuffer>;
+
+    #[view(getMaxGuildMembers)]
+    #[storage_mapper("maxGuildMembers")]
+    fn max_guild_members(&self) -> SingleValueMapper<usize>;
+}
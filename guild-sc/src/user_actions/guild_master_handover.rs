@@ -0,0 +1,160 @@
+mod guild_factory_proxy {
+    multiversx_sc::imports!();
+
+    #[multiversx_sc::proxy]
+    pub trait GuildFactoryProxy {
+        #[endpoint(changeGuildMaster)]
+        fn change_guild_master(&self, new_guild_master: ManagedAddress);
+    }
+}
+
+multiversx_sc::imports!();
+
+use fixed_supply_token::FixedSupplyToken;
+use permissions_module::Permissions;
+
+use crate::{
+    contexts::storage_cache::StorageCache,
+    farm_base_impl::base_traits_impl::{FarmContract, FarmStakingWrapper},
+    tokens::token_attributes::StakingFarmTokenAttributes,
+};
+
+#[multiversx_sc::module]
+pub trait GuildMasterHandoverModule:
+    crate::custom_rewards::CustomRewardsModule
+    + crate::rewards::RewardsModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + crate::tokens::request_id::RequestIdModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + utils::UtilsModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+    + super::custom_events::CustomEventsModule
+    + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+{
+    /// The guild master's farm tokens are kept by the guild until the proposal is accepted or cancelled
+    #[payable("*")]
+    #[endpoint(proposeGuildMaster)]
+    fn propose_guild_master(&self, new_guild_master: ManagedAddress) {
+        self.require_not_closing();
+        self.require_not_globally_paused();
+
+        let caller = self.blockchain().get_caller();
+        let guild_master = self.guild_master_address().get();
+        require!(
+            caller == guild_master,
+            "Only guild master may propose a new guild master"
+        );
+        require!(
+            new_guild_master != guild_master && !new_guild_master.is_zero(),
+            "Invalid new guild master"
+        );
+        require!(
+            self.pending_guild_master().is_empty(),
+            "Guild master handover already in progress"
+        );
+
+        let farm_token_mapper = self.farm_token();
+        let payments = self.get_non_empty_payments();
+        farm_token_mapper.require_all_same_token(&payments);
+
+        let mut total_payment = BigUint::zero();
+        for payment in &payments {
+            let attributes: StakingFarmTokenAttributes<Self::Api> =
+                self.get_attributes_as_part_of_fixed_supply(&payment, &farm_token_mapper);
+            total_payment += attributes.get_total_supply();
+        }
+
+        let total_guild_master_tokens = self.guild_master_tokens().get();
+        require!(
+            total_payment == total_guild_master_tokens,
+            "Must send all tokens when proposing new guild master"
+        );
+
+        self.pending_guild_master().set(new_guild_master);
+        self.guild_master_handover_tokens().set(payments);
+    }
+
+    #[endpoint(cancelGuildMasterProposal)]
+    fn cancel_guild_master_proposal(&self) {
+        let caller = self.blockchain().get_caller();
+        let guild_master = self.guild_master_address().get();
+        require!(
+            caller == guild_master,
+            "Only guild master may cancel the proposal"
+        );
+        require!(
+            !self.pending_guild_master().is_empty(),
+            "No guild master handover in progress"
+        );
+
+        self.pending_guild_master().clear();
+        let handover_tokens = self.guild_master_handover_tokens().take();
+        for payment in &handover_tokens {
+            self.send_payment_non_zero(&caller, &payment);
+        }
+    }
+
+    #[endpoint(acceptGuildMaster)]
+    fn accept_guild_master(&self) {
+        self.require_not_closing();
+        self.require_not_globally_paused();
+
+        let caller = self.blockchain().get_caller();
+        let pending_mapper = self.pending_guild_master();
+        require!(
+            !pending_mapper.is_empty() && pending_mapper.get() == caller,
+            "Only the proposed guild master may accept"
+        );
+        require!(
+            self.user_tokens(&caller).get() == 0,
+            "New guild master must not have staked tokens"
+        );
+
+        // settle any boosted rewards the new guild master earned as a user
+        self.update_boosted_yields_for_user(&caller);
+
+        // the outgoing guild master keeps the commission earned until the handover
+        let old_guild_master = self.guild_master_address().get();
+        let mut storage_cache = StorageCache::new(self);
+        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+        self.pay_guild_master_commission(&old_guild_master, &mut storage_cache);
+
+        let guild_factory = self.blockchain().get_owner_address();
+        let _: IgnoreValue = self
+            .handover_factory_proxy(guild_factory)
+            .change_guild_master(caller.clone())
+            .execute_on_dest_context();
+
+        self.guild_master_address().set(&caller);
+        pending_mapper.clear();
+
+        self.remove_permissions(old_guild_master.clone(), Permissions::ADMIN);
+        self.add_permissions(caller.clone(), Permissions::ADMIN);
+
+        let handover_tokens = self.guild_master_handover_tokens().take();
+        for payment in &handover_tokens {
+            self.send_payment_non_zero(&caller, &payment);
+        }
+
+        self.emit_guild_master_changed_event(&old_guild_master, &caller);
+    }
+
+    #[proxy]
+    fn handover_factory_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> guild_factory_proxy::Proxy<Self::Api>;
+
+    #[view(getPendingGuildMaster)]
+    #[storage_mapper("pendingGuildMaster")]
+    fn pending_guild_master(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("guildMasterHandoverTokens")]
+    fn guild_master_handover_tokens(&self) -> SingleValueMapper<ManagedVec<EsdtTokenPayment>>;
+}
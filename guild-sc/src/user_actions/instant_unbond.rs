@@ -0,0 +1,181 @@
+mod guild_factory_proxy {
+    multiversx_sc::imports!();
+
+    #[multiversx_sc::proxy]
+    pub trait GuildFactoryProxy {
+        #[payable("*")]
+        #[endpoint(depositGuildFees)]
+        fn deposit_guild_fees(&self);
+    }
+}
+
+multiversx_sc::imports!();
+
+use common_structs::{Epoch, Percent};
+use farm::ExitFarmWithPartialPosResultType;
+
+use crate::{
+    contexts::storage_cache::StorageCache, custom_rewards::MAX_PERCENT,
+    tokens::token_attributes::UnbondSftAttributes,
+};
+
+#[multiversx_sc::module]
+pub trait InstantUnbondModule:
+    super::unstake_farm::UnstakeFarmModule
+    + crate::custom_rewards::CustomRewardsModule
+    + crate::tokens::unbond_token::UnbondTokenModule
+    + crate::tokens::request_id::RequestIdModule
+    + crate::rewards::RewardsModule
+    + crate::config::ConfigModule
+    + crate::events::EventsModule
+    + token_send::TokenSendModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + crate::farm_base_impl::base_farm_validation::BaseFarmValidationModule
+    + crate::farm_base_impl::exit_farm::BaseExitFarmModule
+    + utils::UtilsModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+    + crate::tiered_rewards::call_config::CallConfigModule
+    + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+{
+    /// Same as unstakeFarm, but the farming tokens are released immediately, minus the full fee
+    #[payable("*")]
+    #[endpoint(unstakeFarmInstant)]
+    fn unstake_farm_instant(&self) -> ExitFarmWithPartialPosResultType<Self::Api> {
+        self.require_not_closing();
+        let max_fee = self.require_instant_unbond_available();
+
+        let caller = self.blockchain().get_caller();
+        let payments = self.get_non_empty_payments();
+        let unstake_result = self.multi_unstake(&caller, &payments);
+
+        self.require_over_min_stake(&caller);
+
+        let mut farming_tokens = unstake_result.farming_tokens_payment;
+        self.call_decrease_total_staked_tokens(farming_tokens.amount.clone());
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let unlock_epoch = current_epoch + self.get_min_unbond_epochs_user();
+        let fee = self.calculate_instant_unbond_fee(&farming_tokens.amount, unlock_epoch, max_fee);
+        farming_tokens.amount -= &fee;
+        self.collect_instant_unbond_fee(fee);
+        self.send_payment_non_zero(&caller, &farming_tokens);
+
+        self.emit_exit_farm_event(
+            &caller,
+            unstake_result.original_attributes,
+            farming_tokens.clone(),
+            unstake_result.base_rewards_payment.clone(),
+            StorageCache::new(self),
+        );
+
+        (farming_tokens, unstake_result.base_rewards_payment).into()
+    }
+
+    /// Releases the farming tokens of unbond tokens before their unlock epoch.
+    /// The fee decays linearly with the remaining epochs.
+    #[payable("*")]
+    #[endpoint(instantUnbond)]
+    fn instant_unbond(&self) -> EsdtTokenPayment {
+        self.require_not_closing();
+        let max_fee = self.require_instant_unbond_available();
+
+        let storage_cache = StorageCache::new(self);
+        self.validate_contract_state(storage_cache.contract_state, &storage_cache.farm_token_id);
+
+        let unbond_token_mapper = self.unbond_token();
+        let payments = self.get_non_empty_payments();
+        let mut total_farming_tokens = BigUint::zero();
+        let mut total_fee = BigUint::zero();
+        for payment in &payments {
+            unbond_token_mapper.require_same_token(&payment.token_identifier);
+
+            let attributes: UnbondSftAttributes<Self::Api> =
+                self.get_attributes_as_part_of_fixed_supply(&payment, &unbond_token_mapper);
+            total_fee += self.calculate_instant_unbond_fee(
+                &payment.amount,
+                attributes.unlock_epoch,
+                max_fee,
+            );
+
+            unbond_token_mapper.nft_burn(payment.token_nonce, &payment.amount);
+
+            total_farming_tokens += payment.amount;
+        }
+
+        let farming_tokens = EsdtTokenPayment::new(
+            storage_cache.farming_token_id.clone(),
+            0,
+            total_farming_tokens - &total_fee,
+        );
+        self.collect_instant_unbond_fee(total_fee);
+
+        let caller = self.blockchain().get_caller();
+        self.send_payment_non_zero(&caller, &farming_tokens);
+
+        farming_tokens
+    }
+
+    /// Returns the fee for the full unbond period
+    fn require_instant_unbond_available(&self) -> Percent {
+        self.require_not_globally_paused();
+
+        let opt_max_fee = self.get_instant_unbond_fee();
+        require!(opt_max_fee.is_some(), "Instant unbond not available");
+
+        unsafe { opt_max_fee.unwrap_unchecked() }
+    }
+
+    fn calculate_instant_unbond_fee(
+        &self,
+        amount: &BigUint,
+        unlock_epoch: Epoch,
+        max_fee: Percent,
+    ) -> BigUint {
+        let current_epoch = self.blockchain().get_block_epoch();
+        if current_epoch >= unlock_epoch {
+            return BigUint::zero();
+        }
+
+        let remaining_epochs = unlock_epoch - current_epoch;
+        let unbond_epochs = self.get_min_unbond_epochs_user();
+        let fee_percentage = if remaining_epochs >= unbond_epochs {
+            max_fee
+        } else {
+            max_fee * remaining_epochs / unbond_epochs
+        };
+
+        amount * fee_percentage / MAX_PERCENT
+    }
+
+    /// The fee is added to the guild's rewards if possible, otherwise to the factory's rewards pool
+    fn collect_instant_unbond_fee(&self, fee: BigUint) {
+        if fee == 0 {
+            return;
+        }
+
+        let farming_token_id = self.farming_token_id().get();
+        if farming_token_id == self.reward_token_id().get() {
+            self.reward_capacity().update(|r| *r += fee);
+
+            return;
+        }
+
+        let guild_factory = self.blockchain().get_owner_address();
+        let _: IgnoreValue = self
+            .fees_factory_proxy(guild_factory)
+            .deposit_guild_fees()
+            .with_esdt_transfer((farming_token_id, 0, fee))
+            .execute_on_dest_context();
+    }
+
+    #[proxy]
+    fn fees_factory_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> guild_factory_proxy::Proxy<Self::Api>;
+}
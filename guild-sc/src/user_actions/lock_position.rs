@@ -0,0 +1,111 @@
+multiversx_sc::imports!();
+
+use common_structs::Epoch;
+use farm::base_functions::ClaimRewardsResultType;
+
+use crate::{
+    farm_base_impl::base_traits_impl::FarmStakingWrapper, tokens::token_attributes::PositionLock,
+};
+
+#[multiversx_sc::module]
+pub trait LockPositionModule:
+    crate::custom_rewards::CustomRewardsModule
+    + crate::rewards::RewardsModule
+    + crate::config::ConfigModule
+    + crate::events::EventsModule
+    + token_send::TokenSendModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + crate::tokens::request_id::RequestIdModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + crate::farm_base_impl::base_farm_validation::BaseFarmValidationModule
+    + crate::farm_base_impl::claim_rewards::BaseClaimRewardsModule
+    + utils::UtilsModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+    + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + crate::locked_positions::LockedPositionsModule
+    + super::referrals::ReferralsModule
+{
+    /// Locks the position for one of the config SC's lock options, claiming its rewards.
+    /// Locked positions may be extended, but not shortened.
+    #[payable("*")]
+    #[endpoint(lockPosition)]
+    fn lock_position(&self, lock_epochs: Epoch) -> ClaimRewardsResultType<Self::Api> {
+        self.require_not_closing();
+        self.require_not_globally_paused();
+
+        let caller = self.blockchain().get_caller();
+        let guild_master = self.guild_master_address().get();
+        require!(
+            caller != guild_master,
+            "Guild master may not lock positions"
+        );
+
+        let opt_multiplier = self.get_lock_multiplier(lock_epochs);
+        require!(opt_multiplier.is_some(), "Invalid lock option");
+
+        let payment = self.call_value().single_esdt();
+        let mut claim_result = self
+            .claim_rewards_base_no_farm_token_mint::<FarmStakingWrapper<Self>>(
+                caller.clone(),
+                ManagedVec::from_single_item(payment),
+            );
+        self.accrue_referral_rewards(
+            &caller,
+            &claim_result.rewards,
+            &mut claim_result.storage_cache,
+        );
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let new_lock = PositionLock {
+            unlock_epoch: current_epoch + lock_epochs,
+            multiplier: unsafe { opt_multiplier.unwrap_unchecked() },
+        };
+        let attributes = &mut claim_result.new_farm_token.attributes;
+        if let Some(old_lock) = attributes.get_active_lock(current_epoch) {
+            require!(
+                new_lock.unlock_epoch >= old_lock.unlock_epoch,
+                "Lock may not be shortened"
+            );
+
+            self.remove_position_lock_weight(&attributes.current_farm_amount, &old_lock);
+        }
+
+        self.add_position_lock_weight(&attributes.current_farm_amount, &new_lock);
+        attributes.lock = Some(new_lock);
+
+        let new_farm_token = self.farm_token().nft_create(
+            claim_result.new_farm_token.payment.amount.clone(),
+            &claim_result.new_farm_token.attributes,
+        );
+        claim_result.new_farm_token.payment = new_farm_token;
+
+        let accrued_boosted_rewards = self.claim_boosted_yields_rewards(&caller);
+        let boosted_rewards = self.take_payable_rewards(
+            &caller,
+            accrued_boosted_rewards,
+            &mut claim_result.storage_cache.reward_reserve,
+        );
+
+        let total_rewards = claim_result.rewards + boosted_rewards;
+        let reward_token_id = self.reward_token_id().get();
+        let base_rewards_payment = EsdtTokenPayment::new(reward_token_id, 0, total_rewards);
+
+        self.send_payment_non_zero(&caller, &claim_result.new_farm_token.payment);
+        self.send_payment_non_zero(&caller, &base_rewards_payment);
+
+        self.emit_claim_rewards_event(
+            &caller,
+            claim_result.context,
+            claim_result.new_farm_token.clone(),
+            base_rewards_payment.clone(),
+            claim_result.created_with_merge,
+            claim_result.storage_cache,
+        );
+
+        (claim_result.new_farm_token.payment, base_rewards_payment).into()
+    }
+}
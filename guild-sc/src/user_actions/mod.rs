@@ -1,8 +1,17 @@
 pub mod claim_stake_farm_rewards;
 pub mod close_guild;
 pub mod compound_stake_farm_rewards;
+pub mod compound_vault;
 pub mod custom_events;
+pub mod delegates;
+pub mod guild_access;
+pub mod guild_master_handover;
+pub mod instant_unbond;
+pub mod lock_position;
 pub mod migration;
+pub mod referrals;
+pub mod relayed_actions;
 pub mod stake_farm;
+pub mod transfer_position;
 pub mod unbond_farm;
 pub mod unstake_farm;
@@ -0,0 +1,146 @@
+multiversx_sc::imports!();
+
+use common_structs::{Epoch, Percent};
+
+use crate::{
+    contexts::storage_cache::StorageCache, custom_rewards::MAX_PERCENT,
+    farm_base_impl::base_traits_impl::FarmStakingWrapper,
+};
+
+pub const MAX_REFERRAL_PERCENTAGE: Percent = 2_500;
+
+/// Referrers earn a percentage of their referees' claimed rewards for a limited number of epochs.
+/// Referral rewards are paid out of the guild's rewards, on top of the referees' rewards.
+#[multiversx_sc::module]
+pub trait ReferralsModule:
+    crate::custom_rewards::CustomRewardsModule
+    + crate::rewards::RewardsModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + crate::tokens::request_id::RequestIdModule
+    + utils::UtilsModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+    + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+{
+    #[endpoint(setReferralProgram)]
+    fn set_referral_program(&self, percentage: Percent, duration_epochs: Epoch) {
+        self.require_not_closing();
+
+        let guild_master = self.guild_master_address().get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == guild_master,
+            "Only guild master may set referral program"
+        );
+        require!(percentage <= MAX_REFERRAL_PERCENTAGE, "Invalid percentage");
+
+        self.referral_percentage().set(percentage);
+        self.referral_duration_epochs().set(duration_epochs);
+    }
+
+    #[endpoint(claimReferralRewards)]
+    fn claim_referral_rewards(&self) -> EsdtTokenPayment {
+        self.require_not_globally_paused();
+
+        let mut storage_cache = StorageCache::new(self);
+        FarmStakingWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
+
+        let caller = self.blockchain().get_caller();
+        let accrued_rewards = self.referral_rewards(&caller).take();
+        let rewards =
+            self.take_payable_rewards(&caller, accrued_rewards, &mut storage_cache.reward_reserve);
+
+        let payment = EsdtTokenPayment::new(storage_cache.reward_token_id.clone(), 0, rewards);
+        self.send_payment_non_zero(&caller, &payment);
+
+        payment
+    }
+
+    /// Referrers may only be set on the user's first stake
+    fn set_referrer(&self, user: &ManagedAddress, referrer: ManagedAddress) {
+        let guild_master = self.guild_master_address().get();
+        require!(
+            user != &guild_master && user != &referrer,
+            "Invalid referrer"
+        );
+        require!(
+            self.user_tokens(user).is_empty() && self.referrer(user).is_empty(),
+            "Referrer may only be set on first stake"
+        );
+
+        let _ = self.referees(&referrer).insert(user.clone());
+        self.referrer(user).set(referrer);
+        self.referral_start_epoch(user)
+            .set(self.blockchain().get_block_epoch());
+    }
+
+    fn accrue_referral_rewards(
+        &self,
+        user: &ManagedAddress,
+        user_rewards: &BigUint,
+        storage_cache: &mut StorageCache<Self>,
+    ) {
+        let referrer_mapper = self.referrer(user);
+        if referrer_mapper.is_empty() || user_rewards == &0 {
+            return;
+        }
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let end_epoch =
+            self.referral_start_epoch(user).get() + self.referral_duration_epochs().get();
+        if current_epoch >= end_epoch {
+            return;
+        }
+
+        let percentage = self.referral_percentage().get();
+        let accumulated_rewards_mapper = self.accumulated_rewards();
+        let remaining_rewards = self.reward_capacity().get() - accumulated_rewards_mapper.get();
+        let referral_rewards =
+            core::cmp::min(user_rewards * percentage / MAX_PERCENT, remaining_rewards);
+        if referral_rewards == 0 {
+            return;
+        }
+
+        accumulated_rewards_mapper.update(|accumulated| *accumulated += &referral_rewards);
+        storage_cache.reward_reserve += &referral_rewards;
+
+        let referrer = referrer_mapper.get();
+        self.total_referral_earnings(&referrer)
+            .update(|total| *total += &referral_rewards);
+        self.referral_rewards(&referrer)
+            .update(|rewards| *rewards += referral_rewards);
+    }
+
+    #[view(getReferralPercentage)]
+    #[storage_mapper("referralPercentage")]
+    fn referral_percentage(&self) -> SingleValueMapper<Percent>;
+
+    #[view(getReferralDurationEpochs)]
+    #[storage_mapper("referralDurationEpochs")]
+    fn referral_duration_epochs(&self) -> SingleValueMapper<Epoch>;
+
+    #[view(getReferrer)]
+    #[storage_mapper("referrer")]
+    fn referrer(&self, user: &ManagedAddress) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("referralStartEpoch")]
+    fn referral_start_epoch(&self, user: &ManagedAddress) -> SingleValueMapper<Epoch>;
+
+    #[view(getReferees)]
+    #[storage_mapper("referees")]
+    fn referees(&self, referrer: &ManagedAddress) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getReferralRewards)]
+    #[storage_mapper("referralRewards")]
+    fn referral_rewards(&self, referrer: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalReferralEarnings)]
+    #[storage_mapper("totalReferralEarnings")]
+    fn total_referral_earnings(&self, referrer: &ManagedAddress) -> SingleValueMapper<BigUint>;
+}
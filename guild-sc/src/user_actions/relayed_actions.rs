@@ -0,0 +1,66 @@
+use farm::{base_functions::ClaimRewardsResultType, ExitFarmWithPartialPosResultType};
+
+multiversx_sc::imports!();
+
+/// Actions relayed by the factory's router, for users with positions in several guilds.
+/// The factory is trusted to pass the user who sent the positions, like it does for `stakeFarm`.
+/// The outputs are sent straight to the user.
+#[multiversx_sc::module]
+pub trait RelayedActionsModule:
+    super::claim_stake_farm_rewards::ClaimStakeFarmRewardsModule
+    + super::compound_stake_farm_rewards::CompoundStakeFarmRewardsModule
+    + super::unstake_farm::UnstakeFarmModule
+{
+    #[payable("*")]
+    #[endpoint(relayedClaimRewards)]
+    fn relayed_claim_rewards(
+        &self,
+        original_caller: ManagedAddress,
+    ) -> ClaimRewardsResultType<Self::Api> {
+        self.require_factory_caller();
+        self.require_not_closing();
+        self.require_not_globally_paused();
+
+        let payments = self.get_non_empty_payments();
+        let (new_farm_token, base_rewards_payment) =
+            self.claim_rewards_common(&original_caller, payments);
+        self.send_payment_non_zero(&original_caller, &new_farm_token);
+        self.send_payment_non_zero(&original_caller, &base_rewards_payment);
+
+        (new_farm_token, base_rewards_payment).into()
+    }
+
+    #[payable("*")]
+    #[endpoint(relayedCompoundRewards)]
+    fn relayed_compound_rewards(&self, original_caller: ManagedAddress) -> EsdtTokenPayment {
+        self.require_factory_caller();
+        self.require_not_closing();
+        self.require_not_globally_paused();
+        self.require_compounding_enabled();
+
+        let payments = self.get_non_empty_payments();
+        let new_farm_token = self.compound_rewards_common(&original_caller, payments);
+        self.send_payment_non_zero(&original_caller, &new_farm_token);
+
+        new_farm_token
+    }
+
+    #[payable("*")]
+    #[endpoint(relayedUnstakeFarm)]
+    fn relayed_unstake_farm(
+        &self,
+        original_caller: ManagedAddress,
+    ) -> ExitFarmWithPartialPosResultType<Self::Api> {
+        self.require_factory_caller();
+
+        let payments = self.get_non_empty_payments();
+
+        self.unstake_farm_common(&original_caller, &payments)
+    }
+
+    fn require_factory_caller(&self) {
+        let caller = self.blockchain().get_caller();
+        let factory_sc_address = self.blockchain().get_owner_address();
+        require!(caller == factory_sc_address, "Only the factory may relay");
+    }
+}
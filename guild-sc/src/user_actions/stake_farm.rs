@@ -4,6 +4,8 @@ use common_structs::PaymentsVec;
 
 use crate::farm_base_impl::base_traits_impl::FarmStakingWrapper;
 
+use super::delegates::DelegateAction;
+
 #[multiversx_sc::module]
 pub trait StakeFarmModule:
     crate::custom_rewards::CustomRewardsModule
@@ -23,16 +25,31 @@ pub trait StakeFarmModule:
     + crate::tiered_rewards::total_tokens::TokenPerTierModule
     + crate::tiered_rewards::call_config::CallConfigModule
     + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + super::referrals::ReferralsModule
+    + super::guild_access::GuildAccessModule
+    + super::delegates::DelegatesModule
 {
+    /// Approved delegates may stake on behalf of the original caller.
+    /// Referrers may only be set by the users themselves.
     #[payable("*")]
     #[endpoint(stakeFarm)]
     fn stake_farm_endpoint(
         &self,
         opt_original_caller: OptionalValue<ManagedAddress>,
+        opt_referrer: OptionalValue<ManagedAddress>,
     ) -> EsdtTokenPayment {
         let caller = self.blockchain().get_caller();
         let original_caller = self.get_orig_caller_from_opt(&caller, opt_original_caller);
         let payments = self.get_non_empty_payments();
+        if let OptionalValue::Some(referrer) = opt_referrer {
+            require!(
+                caller == original_caller,
+                "Only the user may set a referrer"
+            );
+
+            self.set_referrer(&original_caller, referrer);
+        }
 
         self.stake_farm_common(original_caller, payments)
     }
@@ -52,6 +69,7 @@ pub trait StakeFarmModule:
                 "Guild master must stake first"
             );
         }
+        self.require_can_join_guild(&original_caller);
 
         let enter_result =
             self.enter_farm_base::<FarmStakingWrapper<Self>>(original_caller.clone(), payments);
@@ -62,6 +80,7 @@ pub trait StakeFarmModule:
         self.call_increase_total_staked_tokens(enter_farm_amount);
 
         self.require_over_min_stake(&original_caller);
+        self.update_boosted_yields_for_user(&original_caller);
 
         let new_farm_token = enter_result.new_farm_token.payment.clone();
         self.send_payment_non_zero(&original_caller, &new_farm_token);
@@ -86,7 +105,11 @@ pub trait StakeFarmModule:
             OptionalValue::Some(original_caller) => {
                 let factory_sc_address = self.blockchain().get_owner_address();
                 require!(
-                    caller == &factory_sc_address,
+                    caller == &factory_sc_address
+                        || caller == &original_caller
+                        || self
+                            .delegate_actions(&original_caller, caller)
+                            .contains(&DelegateAction::Stake),
                     "May not use original caller arg"
                 );
 
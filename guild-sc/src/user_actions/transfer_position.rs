@@ -0,0 +1,72 @@
+multiversx_sc::imports!();
+
+use fixed_supply_token::FixedSupplyToken;
+
+use crate::tokens::token_attributes::StakingFarmTokenAttributes;
+
+#[multiversx_sc::module]
+pub trait TransferPositionModule:
+    crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + crate::tokens::farm_token::FarmTokenModule
+    + crate::tokens::unbond_token::UnbondTokenModule
+    + pausable::PausableModule
+    + permissions_module::PermissionsModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + utils::UtilsModule
+    + crate::tiered_rewards::read_config::ReadConfigModule
+    + crate::tiered_rewards::total_tokens::TokenPerTierModule
+    + super::custom_events::CustomEventsModule
+    + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + super::guild_access::GuildAccessModule
+{
+    /// Farm tokens move their staked amount to the receiver. Unbond tokens are forwarded as-is.
+    #[payable("*")]
+    #[endpoint(transferPosition)]
+    fn transfer_position(&self, to: ManagedAddress) {
+        self.require_not_closing();
+        self.require_not_globally_paused();
+
+        let caller = self.blockchain().get_caller();
+        let guild_master = self.guild_master_address().get();
+        require!(
+            caller != guild_master && to != guild_master,
+            "Guild master positions may not be transferred"
+        );
+        require!(caller != to && !to.is_zero(), "Invalid receiver");
+
+        let farm_token_mapper = self.farm_token();
+        let unbond_token_mapper = self.unbond_token();
+        let payments = self.get_non_empty_payments();
+        let mut total_farm_tokens = BigUint::zero();
+        for payment in &payments {
+            if payment.token_identifier == farm_token_mapper.get_token_id() {
+                let attributes: StakingFarmTokenAttributes<Self::Api> =
+                    self.get_attributes_as_part_of_fixed_supply(&payment, &farm_token_mapper);
+                total_farm_tokens += attributes.get_total_supply();
+            } else {
+                unbond_token_mapper.require_same_token(&payment.token_identifier);
+            }
+        }
+
+        if total_farm_tokens > 0 {
+            self.require_can_join_guild(&to);
+
+            self.remove_tokens(&caller, &total_farm_tokens);
+            self.add_tokens(&to, &total_farm_tokens);
+
+            self.require_over_min_stake(&caller);
+            self.require_over_min_stake(&to);
+
+            self.update_boosted_yields_for_user(&caller);
+            self.update_boosted_yields_for_user(&to);
+        }
+
+        for payment in &payments {
+            self.send_payment_non_zero(&to, &payment);
+        }
+
+        self.emit_transfer_position_event(&caller, &to, payments);
+    }
+}
@@ -29,6 +29,7 @@ pub trait UnbondFarmModule:
     + crate::tiered_rewards::call_config::CallConfigModule
     + super::custom_events::CustomEventsModule
     + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
 {
     #[payable("*")]
     #[endpoint(unbondFarm)]
@@ -39,24 +40,28 @@ pub trait UnbondFarmModule:
         let unbond_token_mapper = self.unbond_token();
         let payments = self.get_non_empty_payments();
 
+        let caller = self.blockchain().get_caller();
+        let current_epoch = self.blockchain().get_block_epoch();
         let mut total_farming_tokens = BigUint::zero();
         for payment in &payments {
             unbond_token_mapper.require_same_token(&payment.token_identifier);
 
             let attributes: UnbondSftAttributes<Self::Api> =
                 self.get_attributes_as_part_of_fixed_supply(&payment, &unbond_token_mapper);
-            let current_epoch = self.blockchain().get_block_epoch();
-            require!(
-                current_epoch >= attributes.unlock_epoch,
-                "Unbond period not over"
-            );
+            if current_epoch < attributes.unlock_epoch {
+                // immature tokens are returned as-is
+                self.send_payment_non_zero(&caller, &payment);
+
+                continue;
+            }
 
             unbond_token_mapper.nft_burn(payment.token_nonce, &payment.amount);
 
             total_farming_tokens += payment.amount;
         }
 
-        let caller = self.blockchain().get_caller();
+        require!(total_farming_tokens > 0, "Unbond period not over");
+
         let farming_tokens = EsdtTokenPayment::new(
             storage_cache.farming_token_id.clone(),
             0,
@@ -67,6 +72,24 @@ pub trait UnbondFarmModule:
         farming_tokens
     }
 
+    /// The merged token unlocks at the latest unlock epoch of the merged tokens
+    #[payable("*")]
+    #[endpoint(mergeUnbondTokens)]
+    fn merge_unbond_tokens(&self) -> EsdtTokenPayment {
+        self.require_not_globally_paused();
+
+        let caller = self.blockchain().get_caller();
+        let payments = self.get_non_empty_payments();
+        let unbond_token_mapper = self.unbond_token();
+        unbond_token_mapper.require_all_same_token(&payments);
+
+        let merged_attributes: UnbondSftAttributes<Self::Api> =
+            self.merge_from_payments_and_burn(payments, &unbond_token_mapper);
+        let merged_supply = merged_attributes.get_total_supply();
+
+        unbond_token_mapper.nft_create_and_send(&caller, merged_supply, &merged_attributes)
+    }
+
     #[payable("*")]
     #[endpoint(cancelUnbond)]
     fn cancel_unbond(&self) -> EsdtTokenPayment {
@@ -111,6 +134,7 @@ pub trait UnbondFarmModule:
         self.add_total_base_staked_tokens(&initial_farming_tokens);
         self.add_tokens(&caller, &new_attributes.get_total_supply());
         self.call_increase_total_staked_tokens(new_attributes.get_total_supply());
+        self.update_boosted_yields_for_user(&caller);
 
         let total_farm_tokens = new_attributes.get_total_supply();
         let new_farm_token =
@@ -52,29 +52,41 @@ pub trait UnstakeFarmModule:
     + crate::tiered_rewards::total_tokens::TokenPerTierModule
     + crate::tiered_rewards::call_config::CallConfigModule
     + super::close_guild::CloseGuildModule
+    + crate::boosted_yields::BoostedYieldsModule
+    + super::referrals::ReferralsModule
 {
     #[payable("*")]
     #[endpoint(unstakeFarm)]
     fn unstake_farm(&self) -> ExitFarmWithPartialPosResultType<Self::Api> {
         let caller = self.blockchain().get_caller();
         let payments = self.get_non_empty_payments();
-        let unstake_result = self.multi_unstake(&caller, &payments);
 
-        self.require_over_min_stake(&caller);
+        self.unstake_farm_common(&caller, &payments)
+    }
+
+    /// Sends the unbond token and the rewards to the user
+    fn unstake_farm_common(
+        &self,
+        user: &ManagedAddress,
+        payments: &PaymentsVec<Self::Api>,
+    ) -> ExitFarmWithPartialPosResultType<Self::Api> {
+        let unstake_result = self.multi_unstake(user, payments);
+
+        self.require_over_min_stake(user);
 
         let unbond_token_amount = unstake_result.farming_tokens_payment.amount;
         self.call_decrease_total_staked_tokens(unbond_token_amount.clone());
 
         let min_unbond_epochs = self.get_min_unbond_epochs_user();
         let create_unbond_token_result = self.create_and_send_unbond_tokens(
-            &caller,
+            user,
             unbond_token_amount,
             Some(unstake_result.original_attributes.clone()),
             min_unbond_epochs,
         );
 
         self.emit_exit_farm_event(
-            &caller,
+            user,
             unstake_result.original_attributes,
             create_unbond_token_result.unbond_token.clone(),
             unstake_result.base_rewards_payment.clone(),
@@ -93,10 +105,23 @@ pub trait UnstakeFarmModule:
         original_caller: ManagedAddress,
         payment: EsdtTokenPayment,
     ) -> UnstakeCommonNoTokenMintResultType<Self, StakingFarmTokenAttributes<Self::Api>> {
-        let exit_result =
+        let mut exit_result =
             self.exit_farm_base::<FarmStakingWrapper<Self>>(original_caller.clone(), payment);
+        self.accrue_referral_rewards(
+            &original_caller,
+            &exit_result.rewards,
+            &mut exit_result.storage_cache,
+        );
+
+        let mut original_attributes = exit_result.original_token_attributes.clone();
+        let current_epoch = self.blockchain().get_block_epoch();
+        if let Some(lock) = original_attributes.get_active_lock(current_epoch) {
+            require!(self.guild_closing().get(), "Position locked");
+
+            self.remove_position_lock_weight(&original_attributes.current_farm_amount, &lock);
+        }
+        original_attributes.lock = None;
 
-        let original_attributes = exit_result.original_token_attributes.clone();
         let base_tokens_removed = original_attributes.get_initial_farming_tokens();
         self.remove_total_base_staked_tokens(&base_tokens_removed);
         self.remove_tokens(&original_caller, &original_attributes.get_total_supply());
@@ -132,6 +157,12 @@ pub trait UnstakeFarmModule:
             }
         }
 
+        let accrued_boosted_rewards = self.claim_boosted_yields_rewards(caller);
+        let boosted_rewards = self
+            .reward_reserve()
+            .update(|reserve| self.take_payable_rewards(caller, accrued_boosted_rewards, reserve));
+        total_rewards += boosted_rewards;
+
         let reward_token_id = self.reward_token_id().get();
         let reward_payment = EsdtTokenPayment::new(reward_token_id, 0, total_rewards);
         self.send_payment_non_zero(caller, &reward_payment);
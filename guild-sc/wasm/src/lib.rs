@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           41
+// Endpoints:                          114
 // Async Callback:                       1
-// Total number of exported functions:  44
+// Total number of exported functions: 117
 
 #![no_std]
 
@@ -24,19 +24,27 @@ multiversx_sc_wasm_adapter::endpoints! {
         checkLocalRolesSet => check_local_roles_set
         calculateRewardsForGivenPosition => calculate_rewards_for_given_position
         topUpRewards => top_up_rewards
+        setBoostedYieldsRewardsPercentage => set_boosted_yields_rewards_percentage
+        setGuildMasterCommission => set_guild_master_commission_endpoint
+        claimGuildMasterCommission => claim_guild_master_commission
+        claimHeldBackRewards => claim_held_back_rewards
         startProduceRewards => start_produce_rewards_endpoint
         getAccumulatedRewards => accumulated_rewards
         getRewardCapacity => reward_capacity
+        getLastConfigCheckpoint => last_config_checkpoint
         getGuildMasterRewardPerShare => guild_master_rps
         getUserRewardPerShare => user_rps
         getRewardReserve => reward_reserve
+        getUnfundedRewards => unfunded_rewards
+        getHeldBackRewards => held_back_rewards
         getFarmingTokenId => farming_token_id
         getRewardTokenId => reward_token_id
-        getPerBlockRewardAmount => per_block_reward_amount
-        getLastRewardBlockNonce => last_reward_block_nonce
+        getPerSecondRewardAmount => per_second_reward_amount
+        getLastRewardTimestamp => last_reward_timestamp
         getDivisionSafetyConstant => division_safety_constant
         registerFarmToken => register_farm_token
         setTransferRoleFarmToken => set_transfer_role_farm_token
+        setTransferRoleFarmTokenFactory => set_transfer_role_farm_token_factory
         getFarmTokenId => farm_token
         getFarmTokenSupply => farm_token_supply
         addToPauseWhitelist => add_to_pause_whitelist
@@ -53,14 +61,79 @@ multiversx_sc_wasm_adapter::endpoints! {
         compoundRewards => compound_rewards
         unstakeFarm => unstake_farm
         unbondFarm => unbond_farm
+        mergeUnbondTokens => merge_unbond_tokens
         cancelUnbond => cancel_unbond
         registerUnbondToken => register_unbond_token
         setTransferRoleUnbondToken => set_transfer_role_unbond_token
         getUnbondTokenId => unbond_token
         getUserStakedTokens => get_user_staked_tokens
+        getMemberCount => member_count
         closeGuild => close_guild
         migrateToOtherGuild => migrate_to_other_guild
         isGuildClosing => guild_closing
+        getCurrentWeek => get_current_week
+        getBoostedYieldsRewardsPercentage => boosted_yields_rewards_percentage
+        getFirstWeekStartEpoch => first_week_start_epoch
+        getBoostedRewardsForWeek => boosted_rewards_for_week
+        getTotalEnergyForWeek => total_energy_for_week
+        getTotalFarmAmountForWeek => total_farm_amount_for_week
+        getUserBoostedYieldsProgress => user_boosted_yields_progress
+        getUserUnclaimedBoostedRewards => user_unclaimed_boosted_rewards
+        transferPosition => transfer_position
+        proposeGuildMaster => propose_guild_master
+        cancelGuildMasterProposal => cancel_guild_master_proposal
+        acceptGuildMaster => accept_guild_master
+        getPendingGuildMaster => pending_guild_master
+        getPendingRewards => get_pending_rewards
+        setVaultCompoundBounty => set_vault_compound_bounty
+        depositToVault => deposit_to_vault
+        withdrawFromVault => withdraw_from_vault
+        compoundVault => compound_vault_endpoint
+        getVaultBalance => get_vault_balance
+        getVaultCompoundBounty => vault_compound_bounty
+        getVaultShares => vault_shares
+        getTotalVaultShares => total_vault_shares
+        getVaultFarmAmount => vault_farm_amount
+        getGuildMasterCommission => get_guild_master_commission
+        getPendingGuildMasterCommission => pending_guild_master_commission
+        getAccumulatedGuildMasterCommission => accumulated_guild_master_commission
+        setGuildMetadata => set_guild_metadata
+        getGuildSummary => get_guild_summary
+        unstakeFarmInstant => unstake_farm_instant
+        instantUnbond => instant_unbond
+        getUserLockExtraWeight => user_lock_extra_weight
+        lockPosition => lock_position
+        setReferralProgram => set_referral_program
+        claimReferralRewards => claim_referral_rewards
+        getReferralPercentage => referral_percentage
+        getReferralDurationEpochs => referral_duration_epochs
+        getReferrer => referrer
+        getReferees => referees
+        getReferralRewards => referral_rewards
+        getTotalReferralEarnings => total_referral_earnings
+        setGuildAccessMode => set_guild_access_mode
+        addToGuildAllowlist => add_to_guild_allowlist
+        removeFromGuildAllowlist => remove_from_guild_allowlist
+        setMaxGuildMembers => set_max_guild_members
+        revokeGuildInvites => revoke_guild_invites
+        acceptGuildInvite => accept_guild_invite
+        getGuildAccessMode => guild_access_mode
+        getGuildAllowlist => guild_allowlist
+        getGuildInviteNonce => guild_invite_nonce
+        getRevokedGuildInvites => revoked_guild_invites
+        getMaxGuildMembers => max_guild_members
+        approveDelegate => approve_delegate
+        revokeDelegate => revoke_delegate
+        depositDelegatedPositions => deposit_delegated_positions
+        withdrawDelegatedPositions => withdraw_delegated_positions
+        claimRewardsFor => claim_rewards_for
+        compoundRewardsFor => compound_rewards_for
+        getDelegateActions => get_delegate_actions
+        getUserDelegates => user_delegates
+        getDelegatedPositions => delegated_positions
+        relayedClaimRewards => relayed_claim_rewards
+        relayedCompoundRewards => relayed_compound_rewards
+        relayedUnstakeFarm => relayed_unstake_farm
     )
 }
 